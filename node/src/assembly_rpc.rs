@@ -0,0 +1,104 @@
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use liberland_node_runtime::{
+    opaque::{Block, BlockId},
+    pallet_assembly::{AssemblyPalletApi, CandidateProfile, ElectionCalendar, Law, LawState},
+    pallet_voting::Candidate,
+    AccountId, Balance, BlockNumber, Hash, Runtime,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+use std::sync::Arc;
+
+// a candidate of `get_assembly_candidates` with the profile, the candidates are listed
+// in pairs as the ids can not be keys of a JSON object
+pub type CandidateWithProfile = (
+    Candidate,
+    Option<CandidateProfile<AccountId, Balance, Hash>>,
+);
+
+#[rpc]
+pub trait AssemblyRpc {
+    #[rpc(name = "get_assembly_candidates")]
+    fn get_candidates(&self) -> Result<Vec<CandidateWithProfile>>;
+
+    #[rpc(name = "get_election_calendar")]
+    fn get_election_calendar(&self) -> Result<Option<ElectionCalendar<BlockNumber>>>;
+
+    #[rpc(name = "get_current_assemblies")]
+    fn get_current_assemblies(&self) -> Result<Vec<(Candidate, u64)>>;
+
+    #[rpc(name = "get_laws")]
+    fn get_laws(&self, state: LawState) -> Result<BTreeMap<Hash, Law>>;
+
+    #[rpc(name = "get_current_prime_minister")]
+    fn get_current_prime_minister(&self) -> Result<Option<Candidate>>;
+
+    #[rpc(name = "get_prime_min_candidates")]
+    fn get_prime_min_candidates(&self) -> Result<BTreeSet<Candidate>>;
+
+    #[rpc(name = "get_next_election")]
+    fn get_next_election(&self) -> Result<BlockNumber>;
+}
+
+pub struct AssemblyRpcImpl<C> {
+    pub client: Arc<C>,
+}
+
+impl<C> AssemblyRpc for AssemblyRpcImpl<C>
+where
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: AssemblyPalletApi<Block, Runtime>,
+{
+    fn get_candidates(&self) -> Result<Vec<CandidateWithProfile>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_candidates(&best_hash).unwrap();
+        Ok(res)
+    }
+
+    fn get_election_calendar(&self) -> Result<Option<ElectionCalendar<BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_election_calendar(&best_hash).unwrap();
+        Ok(res)
+    }
+
+    fn get_current_assemblies(&self) -> Result<Vec<(Candidate, u64)>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_current_assemblies(&best_hash).unwrap();
+        Ok(res)
+    }
+
+    fn get_laws(&self, state: LawState) -> Result<BTreeMap<Hash, Law>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_laws(&best_hash, state).unwrap();
+        Ok(res)
+    }
+
+    fn get_current_prime_minister(&self) -> Result<Option<Candidate>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_current_prime_minister(&best_hash).unwrap();
+        Ok(res)
+    }
+
+    fn get_prime_min_candidates(&self) -> Result<BTreeSet<Candidate>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_prime_min_candidates(&best_hash).unwrap();
+        Ok(res)
+    }
+
+    fn get_next_election(&self) -> Result<BlockNumber> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_next_election(&best_hash).unwrap();
+        Ok(res)
+    }
+}
@@ -1,6 +1,8 @@
+pub mod assembly_rpc;
 pub mod chain_spec;
 pub mod identity_rpc;
 pub mod min_interior_rpc;
 pub mod referendum_rpc;
 pub mod rpc;
 pub mod service;
+pub mod voting_rpc;
@@ -1,6 +1,7 @@
 //! Liberland Node CLI library.
 #![warn(missing_docs)]
 
+mod assembly_rpc;
 mod chain_spec;
 #[macro_use]
 mod service;
@@ -10,6 +11,7 @@ mod identity_rpc;
 mod min_interior_rpc;
 mod referendum_rpc;
 mod rpc;
+mod voting_rpc;
 
 fn main() -> sc_cli::Result<()> {
     command::run()
@@ -2,24 +2,51 @@ use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 use liberland_node_runtime::{
     opaque::{Block, BlockId},
-    pallet_referendum::{ReferendumPalletApi, Suggestion},
-    Hash, Runtime,
+    pallet_referendum::{ClosedSuggestion, ReferendumPalletApi, Suggestion},
+    BlockNumber, Hash, Runtime,
 };
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_std::collections::btree_map::BTreeMap;
 use std::sync::Arc;
 
+// the texts of the suggestions are checked to be UTF-8 by the runtime
+fn text(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn texts(
+    suggestions: BTreeMap<Hash, Suggestion<Hash>>,
+) -> BTreeMap<Hash, Suggestion<Hash, String>> {
+    suggestions
+        .into_iter()
+        .map(|(subject, suggestion)| (subject, suggestion.map_text(text)))
+        .collect()
+}
+
 #[rpc]
 pub trait ReferendumRpc {
     #[rpc(name = "get_active_petitions")]
-    fn get_active_petitions(&self) -> Result<BTreeMap<Hash, Suggestion>>;
+    fn get_active_petitions(&self) -> Result<BTreeMap<Hash, Suggestion<Hash, String>>>;
 
     #[rpc(name = "get_active_referendums")]
-    fn get_active_referendums(&self) -> Result<BTreeMap<Hash, Suggestion>>;
+    fn get_active_referendums(&self) -> Result<BTreeMap<Hash, Suggestion<Hash, String>>>;
 
     #[rpc(name = "get_successfull_referendums")]
-    fn get_successfull_referendums(&self) -> Result<BTreeMap<Hash, Suggestion>>;
+    fn get_successfull_referendums(&self) -> Result<BTreeMap<Hash, Suggestion<Hash, String>>>;
+
+    #[rpc(name = "get_suggestion")]
+    fn get_suggestion(&self, subject: Hash) -> Result<Option<Suggestion<Hash, String>>>;
+
+    #[rpc(name = "get_closed_suggestions_count")]
+    fn get_closed_suggestions_count(&self) -> Result<u32>;
+
+    #[rpc(name = "get_closed_suggestions")]
+    fn get_closed_suggestions(
+        &self,
+        start: u32,
+        count: u32,
+    ) -> Result<Vec<ClosedSuggestion<Hash, BlockNumber, String>>>;
 }
 
 pub struct ReferendumRpcImpl<C> {
@@ -33,24 +60,54 @@ where
     C: HeaderBackend<Block>,
     C::Api: ReferendumPalletApi<Block, Runtime>,
 {
-    fn get_active_petitions(&self) -> Result<BTreeMap<Hash, Suggestion>> {
+    fn get_active_petitions(&self) -> Result<BTreeMap<Hash, Suggestion<Hash, String>>> {
         let api = self.client.runtime_api();
         let best_hash = BlockId::hash(self.client.info().best_hash);
         let res = api.get_active_petitions(&best_hash).unwrap();
-        Ok(res)
+        Ok(texts(res))
     }
 
-    fn get_active_referendums(&self) -> Result<BTreeMap<Hash, Suggestion>> {
+    fn get_active_referendums(&self) -> Result<BTreeMap<Hash, Suggestion<Hash, String>>> {
         let api = self.client.runtime_api();
         let best_hash = BlockId::hash(self.client.info().best_hash);
         let res = api.get_active_referendums(&best_hash).unwrap();
-        Ok(res)
+        Ok(texts(res))
     }
 
-    fn get_successfull_referendums(&self) -> Result<BTreeMap<Hash, Suggestion>> {
+    fn get_successfull_referendums(&self) -> Result<BTreeMap<Hash, Suggestion<Hash, String>>> {
         let api = self.client.runtime_api();
         let best_hash = BlockId::hash(self.client.info().best_hash);
         let res = api.get_successfull_referendums(&best_hash).unwrap();
+        Ok(texts(res))
+    }
+
+    fn get_suggestion(&self, subject: Hash) -> Result<Option<Suggestion<Hash, String>>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_suggestion(&best_hash, subject).unwrap();
+        Ok(res.map(|suggestion| suggestion.map_text(text)))
+    }
+
+    fn get_closed_suggestions_count(&self) -> Result<u32> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_closed_suggestions_count(&best_hash).unwrap();
         Ok(res)
     }
+
+    fn get_closed_suggestions(
+        &self,
+        start: u32,
+        count: u32,
+    ) -> Result<Vec<ClosedSuggestion<Hash, BlockNumber, String>>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api
+            .get_closed_suggestions(&best_hash, start, count)
+            .unwrap();
+        Ok(res
+            .into_iter()
+            .map(|closed| closed.map_text(text))
+            .collect())
+    }
 }
@@ -7,12 +7,15 @@
 
 use std::sync::Arc;
 
+use crate::assembly_rpc;
 use crate::identity_rpc;
 use crate::min_interior_rpc;
 use crate::referendum_rpc;
+use crate::voting_rpc;
 use liberland_node_runtime::{
-    opaque::Block, pallet_identity::IdentityPalletApi, pallet_min_interior::MinInteriorPalletApi,
-    pallet_referendum::ReferendumPalletApi, AccountId, Balance, Index, Runtime,
+    opaque::Block, pallet_assembly::AssemblyPalletApi, pallet_identity::IdentityPalletApi,
+    pallet_min_interior::MinInteriorPalletApi, pallet_referendum::ReferendumPalletApi,
+    pallet_voting::VotingPalletApi, AccountId, Balance, Index, Runtime,
 };
 pub use sc_rpc_api::DenyUnsafe;
 use sp_api::ProvideRuntimeApi;
@@ -42,6 +45,8 @@ where
     C::Api: MinInteriorPalletApi<Block, Runtime>,
     C::Api: IdentityPalletApi<Block, Runtime>,
     C::Api: ReferendumPalletApi<Block, Runtime>,
+    C::Api: VotingPalletApi<Block, Runtime>,
+    C::Api: AssemblyPalletApi<Block, Runtime>,
     P: TransactionPool + 'static,
 {
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
@@ -78,7 +83,19 @@ where
     ));
 
     io.extend_with(referendum_rpc::ReferendumRpc::to_delegate(
-        referendum_rpc::ReferendumRpcImpl { client },
+        referendum_rpc::ReferendumRpcImpl {
+            client: client.clone(),
+        },
+    ));
+
+    io.extend_with(voting_rpc::VotingRpc::to_delegate(
+        voting_rpc::VotingRpcImpl {
+            client: client.clone(),
+        },
+    ));
+
+    io.extend_with(assembly_rpc::AssemblyRpc::to_delegate(
+        assembly_rpc::AssemblyRpcImpl { client },
     ));
 
     // Extend this RPC with a custom API by using the following syntax.
@@ -0,0 +1,66 @@
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use liberland_node_runtime::{
+    opaque::{Block, BlockId},
+    pallet_voting::{ElectionResult, VotingInfo, VotingPalletApi},
+    BlockNumber, Hash, Runtime,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_std::collections::btree_map::BTreeMap;
+use std::sync::Arc;
+
+#[rpc]
+pub trait VotingRpc {
+    #[rpc(name = "get_election_results")]
+    fn get_election_results(&self, subject: Hash) -> Result<BTreeMap<BlockNumber, ElectionResult>>;
+
+    #[rpc(name = "get_last_election_result")]
+    fn get_last_election_result(&self, subject: Hash) -> Result<Option<ElectionResult>>;
+
+    #[rpc(name = "get_active_votings")]
+    fn get_active_votings(&self) -> Result<BTreeMap<Hash, VotingInfo<BlockNumber>>>;
+
+    #[rpc(name = "get_active_voting")]
+    fn get_active_voting(&self, subject: Hash) -> Result<Option<VotingInfo<BlockNumber>>>;
+}
+
+pub struct VotingRpcImpl<C> {
+    pub client: Arc<C>,
+}
+
+impl<C> VotingRpc for VotingRpcImpl<C>
+where
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: VotingPalletApi<Block, Runtime>,
+{
+    fn get_election_results(&self, subject: Hash) -> Result<BTreeMap<BlockNumber, ElectionResult>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_election_results(&best_hash, subject).unwrap();
+        Ok(res)
+    }
+
+    fn get_last_election_result(&self, subject: Hash) -> Result<Option<ElectionResult>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_last_election_result(&best_hash, subject).unwrap();
+        Ok(res)
+    }
+
+    fn get_active_votings(&self) -> Result<BTreeMap<Hash, VotingInfo<BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_active_votings(&best_hash).unwrap();
+        Ok(res)
+    }
+
+    fn get_active_voting(&self, subject: Hash) -> Result<Option<VotingInfo<BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let best_hash = BlockId::hash(self.client.info().best_hash);
+        let res = api.get_active_voting(&best_hash, subject).unwrap();
+        Ok(res)
+    }
+}
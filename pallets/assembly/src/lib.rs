@@ -1,10 +1,21 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use frame_support::codec::{Decode, Encode};
+use frame_support::sp_runtime::traits::IntegerSquareRoot;
+use frame_support::sp_runtime::{PerThing, Percent};
+use frame_support::traits::{Contains, Currency, EnsureOrigin, ReservableCurrency};
 pub use pallet::*;
 use pallet_identity::{IdentityTrait, IdentityType, PassportId};
-use pallet_voting::{AltVote, AltVoutingSettings, Candidate, VotingTrait};
+use pallet_voting::{
+    AltVote, BallotsView, Candidate, CountResult, CountingMethod, DelegationTopic, VotingRules,
+    VotingSettings, VotingTrait,
+};
 use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
 use sp_std::convert::TryInto;
+use sp_std::vec::Vec;
+
+pub type BalanceOf<T> =
+    <<T as Config>::DepositCurrency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 #[cfg(test)]
 mod mock;
 
@@ -16,9 +27,14 @@ pub mod pallet {
 
     use super::*;
     use frame_support::pallet_prelude::*;
-    use frame_support::sp_runtime::traits::Zero;
+    use frame_support::sp_runtime::traits::{One, Zero};
+    use frame_support::{StorageHasher, Twox128};
     use frame_system::pallet_prelude::*;
     use pallet_staking::StakingTrait;
+
+    // the storage of the powers the citizens voted with, it was replaced by `Supports`
+    const OLD_BALLOTS: &[u8] = b"Ballots";
+
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
     pub trait Config:
@@ -27,6 +43,8 @@ pub mod pallet {
         + pallet_voting::Config
         + pallet_staking::Config
     {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
         #[pallet::constant]
         type AssemblyElectionPeriod: Get<Self::BlockNumber>;
 
@@ -45,23 +63,97 @@ pub mod pallet {
         #[pallet::constant]
         type WinnersAmount: Get<u32>;
 
+        #[pallet::constant]
+        type AssemblyCountingMethod: Get<CountingMethod>;
+
+        // the power of the ballots and the support of a citizen computed from the liber stake
+        #[pallet::constant]
+        type AssemblyVotingPower: Get<VotingPower>;
+
+        // the reveal window of the assembly voting, `None` keeps the ballots open
+        #[pallet::constant]
+        type AssemblyRevealDuration: Get<Option<Self::BlockNumber>>;
+
         #[pallet::constant]
         type PrimeMinVotingDuration: Get<Self::BlockNumber>;
 
         #[pallet::constant]
         type PrimeMinVotingDelay: Get<Self::BlockNumber>;
 
+        type DepositCurrency: ReservableCurrency<Self::AccountId>;
+
+        // reserved for a candidacy, returned when the candidacy is withdrawn or the candidate
+        // is elected or gets at least `CandidateDepositThreshold` of the first preferences,
+        // slashed otherwise
+        #[pallet::constant]
+        type CandidateDeposit: Get<BalanceOf<Self>>;
+
+        #[pallet::constant]
+        type CandidateDepositThreshold: Get<Percent>;
+
+        // the longest display name of a candidate
+        #[pallet::constant]
+        type MaxDisplayNameLength: Get<u32>;
+
         type IdentTrait: IdentityTrait<Self>;
 
         type VotingTrait: pallet_voting::VotingTrait<Self>;
 
         type StakingTrait: pallet_staking::StakingTrait<Self>;
+
+        // the binding referendums of the citizens, they override the laws decided by the
+        // assembly and set its voting power
+        type ReferendumOrigin: EnsureOrigin<Self::Origin>;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(trait Store)]
     pub struct Pallet<T>(_);
 
+    #[pallet::event]
+    #[pallet::metadata(
+        T::AccountId = "AccountId",
+        T::Hash = "Hash",
+        T::BlockNumber = "BlockNumber"
+    )]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        // the citizen registered as a candidate of the next assembly election
+        CandidateAdded(Candidate),
+        // the candidate withdrew before the assembly voting started
+        CandidacyWithdrawn(Candidate),
+        // the assembly voting of the term which starts in the block is opened
+        AssemblyVotingStarted(T::BlockNumber),
+        // the citizen voted in the assembly voting openly or by a commitment
+        AssemblyVoteCast(T::AccountId),
+        // the citizen revealed the committed ballot
+        AssemblyVoteRevealed(T::AccountId),
+        // the new members of the assembly with their power
+        AssemblyElected(BTreeMap<Candidate, u64>),
+        // the member of the assembly registered as a candidate for the prime minister
+        PrimeMinCandidateAdded(Candidate),
+        // the assembly voting could not be created, it is retried in the next block
+        AssemblyVotingNotStarted(DispatchError),
+        PrimeMinVotingStarted,
+        // the prime minister voting could not be created, it is retried in the next block
+        PrimeMinVotingNotStarted(DispatchError),
+        // the member of the assembly voted for the prime minister
+        PrimeMinVoteCast(T::AccountId),
+        PrimeMinisterElected(Candidate),
+        // the member of the assembly proposed the law
+        LawProposed(T::Hash, LawType),
+        // the member of the assembly voted on the law
+        LawVoteCast(T::Hash, T::AccountId, Decision),
+        LawDecided(T::Hash, LawState),
+        // a referendum overrode the decision of the assembly on the law
+        LawOverridden(T::Hash, LawState),
+        // a referendum set the voting power which replaces `AssemblyVotingPower`
+        VotingPowerChanged(VotingPower),
+        // the support of the citizen for the member changed, with the support of the citizen
+        // for the member and the power of the member after the change
+        SupportChanged(T::AccountId, Candidate, u64, u64),
+    }
+
     #[pallet::error]
     pub enum Error<T> {
         AccountCannotBeAddedAsCandiate,
@@ -76,40 +168,54 @@ pub mod pallet {
         NoSuchBallot,
         ChangePowerTooBig,
         AccountCannotSupport,
+        DelegateIsNotCitizen,
+        AlreadyCandidate,
+        NotCandidate,
+        DisplayNameTooLong,
+        InsufficientDeposit,
+        LawNotFound,
+        LawVotingInProgress,
+        InvalidLawState,
     }
 
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        // the votings which can not be created are retried in the next block, the weight of
+        // the prime minister voting opened in `on_finalize` is taken here
         fn on_initialize(block_number: BlockNumberFor<T>) -> frame_support::weights::Weight {
-            if (block_number
-                % (T::AssemblyElectionPeriod::get() + T::AssemblyVotingDuration::get()))
-            .is_zero()
-            {
-                Self::initialize();
-                <VotingState<T>>::mutate(|state| *state = true);
+            // the calendar is read here and in `on_finalize`
+            let mut weight = T::DbWeight::get().reads(2);
+            if <Calendar<T>>::get().map_or(false, |calendar| {
+                calendar.prime_min_voting_start == block_number
+            }) {
+                weight = weight.saturating_add(Self::voting_start_weight());
+            }
+            if block_number >= Self::next_term_start() {
+                weight = weight.saturating_add(Self::voting_start_weight());
+                match Self::initialize() {
+                    Ok(snapshot_weight) => {
+                        weight = weight.saturating_add(snapshot_weight);
+                        <Calendar<T>>::put(Self::term_calendar(block_number));
+                        <VotingState<T>>::mutate(|state| *state = true);
+                        Self::deposit_event(Event::AssemblyVotingStarted(block_number));
+                    }
+                    Err(error) => Self::deposit_event(Event::AssemblyVotingNotStarted(
+                        Self::voting_error(error),
+                    )),
+                }
             }
-            0
+            weight
+        }
+
+        fn on_runtime_upgrade() -> frame_support::weights::Weight {
+            Self::remove_old_ballots().saturating_add(Self::seed_calendar())
         }
 
         fn on_finalize(block_number: BlockNumberFor<T>) {
-            let current_block = TryInto::<u64>::try_into(block_number).ok().unwrap();
-            let assembly_voting_duration =
-                TryInto::<u64>::try_into(T::AssemblyVotingDuration::get())
-                    .ok()
-                    .unwrap();
-            let assembly_election_period =
-                TryInto::<u64>::try_into(T::AssemblyElectionPeriod::get())
-                    .ok()
-                    .unwrap();
-            let prime_min_voting_delay = TryInto::<u64>::try_into(T::PrimeMinVotingDelay::get())
-                .ok()
-                .unwrap();
-            let x = (current_block / (assembly_voting_duration + assembly_election_period)) as u64;
-            let sub_block = x * (assembly_voting_duration + assembly_election_period);
-            if (current_block % (sub_block + assembly_voting_duration + prime_min_voting_delay))
-                .is_zero()
-            {
-                Self::start_prime_min_voting();
+            if let Some(calendar) = <Calendar<T>>::get() {
+                if block_number == calendar.prime_min_voting_start {
+                    Self::start_prime_min_voting(calendar);
+                }
             }
         }
     }
@@ -119,6 +225,17 @@ pub mod pallet {
     type CandidatesList<T: Config> =
         StorageValue<_, BTreeSet<Candidate>, ValueQuery, DefaultCandidates>;
 
+    // the profiles of the candidates registered with a deposit
+    #[pallet::storage]
+    #[pallet::getter(fn candidate_profiles)]
+    type CandidateProfiles<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        Candidate,
+        CandidateProfile<T::AccountId, BalanceOf<T>, T::Hash>,
+        OptionQuery,
+    >;
+
     #[pallet::storage]
     #[pallet::getter(fn ministers_list)]
     type CurrentAssembliesList<T: Config> =
@@ -138,6 +255,17 @@ pub mod pallet {
         DefaultVotedAssemblies,
     >;
 
+    // the citizens whose ballots in the assembly voting were cast by delegates with the
+    // accounts the ballots are kept under, any account of the citizen replaces the ballot
+    #[pallet::storage]
+    type DelegatedBallots<T: Config> =
+        StorageMap<_, Blake2_128Concat, PassportId, T::AccountId, OptionQuery>;
+
+    // the calendar of the current term, it is computed when the term starts
+    #[pallet::storage]
+    #[pallet::getter(fn election_calendar)]
+    type Calendar<T: Config> = StorageValue<_, ElectionCalendar<T::BlockNumber>, OptionQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn voting_state)]
     type VotingState<T: Config> = StorageValue<_, bool, ValueQuery, DefaultState>;
@@ -146,14 +274,40 @@ pub mod pallet {
     #[pallet::getter(fn laws)]
     type Laws<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, Law, OptionQuery>;
 
+    // the voting power set by a referendum, it replaces `AssemblyVotingPower`
+    #[pallet::storage]
+    #[pallet::getter(fn voting_power_setting)]
+    type VotingPowerSetting<T: Config> = StorageValue<_, VotingPower, OptionQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn assemblys_stake_amount)]
     type AssemblyStakeAmount<T: Config> = StorageValue<_, u64, ValueQuery, DefaultLiberAmount>;
 
+    // the support of the citizens for the members of the assembly, it starts from the power of
+    // the ballots and the total support of a citizen is capped by the liber stake of the citizen
+    #[pallet::storage]
+    #[pallet::getter(fn supports)]
+    type Supports<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        PassportId,
+        Blake2_128Concat,
+        Candidate,
+        u64,
+        ValueQuery,
+    >;
+
+    // the liber stake of the citizens when the last assembly voting opened, the ballots of the
+    // voting are cast with it, the citizens without the stake are not stored
     #[pallet::storage]
-    #[pallet::getter(fn ballots)]
-    type Ballots<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery, DefaultBallot<T>>;
+    #[pallet::getter(fn stake_snapshot)]
+    type StakeSnapshot<T: Config> = StorageMap<_, Blake2_128Concat, PassportId, u64, ValueQuery>;
+
+    // the power of the members of the assembly when the prime minister voting opened
+    #[pallet::storage]
+    #[pallet::getter(fn prime_min_voting_powers)]
+    type PrimeMinVotingPowers<T: Config> =
+        StorageValue<_, BTreeMap<Candidate, u64>, ValueQuery, DefaultMinisters>;
 
     #[pallet::storage]
     #[pallet::getter(fn current_prime_min)]
@@ -168,11 +322,6 @@ pub mod pallet {
     type VotedForPrimeMinAssemblies<T: Config> =
         StorageValue<_, BTreeSet<PassportId>, ValueQuery, DefaultVotedForPrimeMinAssemblies>;
 
-    #[pallet::type_value]
-    pub fn DefaultBallot<T: Config>() -> u64 {
-        Default::default()
-    }
-
     #[pallet::type_value]
     pub fn DefaultCandidates() -> BTreeSet<Candidate> {
         Default::default()
@@ -208,35 +357,94 @@ pub mod pallet {
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        #[pallet::weight(1)]
+        // charged for `MaxDelegators` ballots cast for the delegators, the rest is refunded
+        #[pallet::weight(1 + Pallet::<T>::delegators_weight(T::MaxDelegators::get()))]
         pub fn vote(origin: OriginFor<T>, ballot: AltVote) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let citizen = Self::ensure_can_vote(sender.clone())?;
+            let power = Self::voting_power(Self::stake_snapshot(citizen));
+            // the ballot replaces the delegated ballot of the citizen under its account
+            let account = <DelegatedBallots<T>>::get(citizen).unwrap_or_else(|| sender.clone());
+            Self::alt_vote(account, ballot.clone(), power)?;
+            <DelegatedBallots<T>>::remove(citizen);
+            <VotedCitizens<T>>::mutate(|voted_citizens| {
+                voted_citizens.insert(citizen);
+            });
+            let delegators = Self::vote_for_delegators(&sender, ballot);
+            Self::deposit_event(Event::AssemblyVoteCast(sender));
+
+            Ok(Some(1 + Self::delegators_weight(delegators)).into())
+        }
+
+        // the delegate votes for the citizen in the assembly elections which the citizen
+        // does not vote in, the delegation is transitive, it applies to the ballots the
+        // delegate casts after it
+        #[pallet::weight(1)]
+        pub(super) fn delegate_vote(
+            origin: OriginFor<T>,
+            delegate: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
             let sender = ensure_signed(origin)?;
             ensure!(
                 T::IdentTrait::check_account_identity(sender.clone(), IdentityType::Citizen),
                 <Error<T>>::AccountCannotVote
             );
-            //this unwrap() is correct
-            let citizen = pallet_identity::Pallet::<T>::passport_id(sender.clone()).unwrap();
             ensure!(
-                !<VotedCitizens<T>>::get().contains(&citizen),
-                <Error<T>>::AlreadyVoted
+                T::IdentTrait::check_account_identity(delegate.clone(), IdentityType::Citizen),
+                <Error<T>>::DelegateIsNotCitizen
             );
-            let mut power: pallet_staking::BalanceOf<T> = Zero::zero();
-            pallet_identity::Pallet::<T>::account_ids(citizen)
-                .iter()
-                .for_each(|account_id| {
-                    power += T::StakingTrait::get_liber_amount(account_id.clone());
-                });
+            T::VotingTrait::delegate(DelegationTopic::AssemblyElections, sender, delegate)?;
+            Ok(().into())
+        }
+
+        #[pallet::weight(1)]
+        pub(super) fn undelegate_vote(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            T::VotingTrait::undelegate(DelegationTopic::AssemblyElections, sender)?;
+            Ok(().into())
+        }
 
-            let power = TryInto::<u64>::try_into(power).ok().unwrap();
-            Self::alt_vote(sender, ballot, power)?;
+        // the secret ballot of the assembly voting, `commitment` is
+        // `pallet_voting::Pallet::commitment_of(sender, AssemblyVotingHash, ballot, salt)`
+        #[pallet::weight(1)]
+        pub fn commit_vote(
+            origin: OriginFor<T>,
+            commitment: T::Hash,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let citizen = Self::ensure_can_vote(sender.clone())?;
+            let power = Self::voting_power(Self::stake_snapshot(citizen));
+            T::VotingTrait::commit_vote(
+                T::AssemblyVotingHash::get(),
+                sender.clone(),
+                commitment,
+                power,
+            )?;
             <VotedCitizens<T>>::mutate(|voted_citizens| {
                 voted_citizens.insert(citizen);
             });
+            Self::deposit_event(Event::AssemblyVoteCast(sender));
 
             Ok(().into())
         }
 
+        #[pallet::weight(1)]
+        pub fn reveal_vote(
+            origin: OriginFor<T>,
+            ballot: AltVote,
+            salt: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            T::VotingTrait::reveal_vote(
+                T::AssemblyVotingHash::get(),
+                sender.clone(),
+                ballot,
+                salt,
+            )?;
+            Self::deposit_event(Event::AssemblyVoteRevealed(sender));
+            Ok(().into())
+        }
+
         #[pallet::weight(1)]
         pub(super) fn vote_to_prime_min(
             origin: OriginFor<T>,
@@ -254,26 +462,82 @@ pub mod pallet {
                 !<VotedForPrimeMinAssemblies<T>>::get().contains(&assembly_id),
                 <Error<T>>::AlreadyVoted
             );
-            let assemblies_list = Self::ministers_list();
-            //this unwrap() is correct
-            let assembly_power = assemblies_list.get(&assembly_id.to_vec()).unwrap();
-            Self::prime_min_alt_vote(sender, ballot, *assembly_power)?;
+            // the members elected after the voting opened have no power in it
+            let assembly_power = Self::prime_min_voting_powers()
+                .get(&assembly_id.to_vec())
+                .copied()
+                .unwrap_or_default();
+            Self::prime_min_alt_vote(sender.clone(), ballot, assembly_power)?;
             <VotedForPrimeMinAssemblies<T>>::mutate(|storage| {
                 storage.insert(assembly_id);
             });
+            Self::deposit_event(Event::PrimeMinVoteCast(sender));
             Ok(().into())
         }
 
+        // registers the citizen as a candidate of the next assembly election with
+        // `CandidateDeposit` reserved, `manifesto` is the hash of the text kept off the chain
         #[pallet::weight(1)]
-        pub(super) fn add_candidate(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+        pub(super) fn add_candidate(
+            origin: OriginFor<T>,
+            display_name: Vec<u8>,
+            manifesto: T::Hash,
+        ) -> DispatchResultWithPostInfo {
             let sender = ensure_signed(origin)?;
             ensure!(
                 !<VotingState<T>>::get(),
                 <Error<T>>::VotingIsAlreadyInProgress
             );
-            let citizen = pallet_identity::Pallet::<T>::passport_id(sender)
+            let citizen = pallet_identity::Pallet::<T>::passport_id(sender.clone())
                 .ok_or(<Error<T>>::AccountCannotBeAddedAsCandiate)?;
+            ensure!(
+                !<CandidatesList<T>>::get().contains(&citizen.to_vec()),
+                <Error<T>>::AlreadyCandidate
+            );
+            ensure!(
+                display_name.len() <= T::MaxDisplayNameLength::get() as usize,
+                <Error<T>>::DisplayNameTooLong
+            );
+            let deposit = T::CandidateDeposit::get();
+            ensure!(
+                T::DepositCurrency::can_reserve(&sender, deposit),
+                <Error<T>>::InsufficientDeposit
+            );
+
             Self::add_candidate_internal(citizen)?;
+            T::DepositCurrency::reserve(&sender, deposit)?;
+            <CandidateProfiles<T>>::insert(
+                citizen.to_vec(),
+                CandidateProfile {
+                    depositor: sender,
+                    deposit,
+                    display_name,
+                    manifesto,
+                },
+            );
+            Self::deposit_event(Event::CandidateAdded(citizen.to_vec()));
+            Ok(().into())
+        }
+
+        // the deposit is returned to the candidates who withdraw before the voting starts
+        #[pallet::weight(1)]
+        pub(super) fn withdraw_candidacy(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            ensure!(
+                !<VotingState<T>>::get(),
+                <Error<T>>::VotingIsAlreadyInProgress
+            );
+            let candidate = pallet_identity::Pallet::<T>::passport_id(sender)
+                .ok_or(<Error<T>>::NotCandidate)?
+                .to_vec();
+            ensure!(
+                <CandidatesList<T>>::mutate(|candidates| candidates.remove(&candidate)),
+                <Error<T>>::NotCandidate
+            );
+            if let Some(profile) = <CandidateProfiles<T>>::take(&candidate) {
+                T::DepositCurrency::unreserve(&profile.depositor, profile.deposit);
+            }
+            Self::deposit_event(Event::CandidacyWithdrawn(candidate));
             Ok(().into())
         }
 
@@ -283,6 +547,7 @@ pub mod pallet {
             let assembly = pallet_identity::Pallet::<T>::passport_id(sender)
                 .ok_or(<Error<T>>::AccountCannotBeAddedAsCandiate)?;
             Self::add_prime_min_candidate_internal(assembly)?;
+            Self::deposit_event(Event::PrimeMinCandidateAdded(assembly.to_vec()));
             Ok(().into())
         }
 
@@ -293,25 +558,10 @@ pub mod pallet {
             law_type: LawType,
         ) -> DispatchResultWithPostInfo {
             let sender = ensure_signed(origin)?;
-            let current_block = TryInto::<u64>::try_into(<frame_system::Pallet<T>>::block_number())
-                .ok()
-                .unwrap();
-            let assembly_voting_duration =
-                TryInto::<u64>::try_into(T::AssemblyVotingDuration::get())
-                    .ok()
-                    .unwrap();
-            let assembly_election_period =
-                TryInto::<u64>::try_into(T::AssemblyElectionPeriod::get())
-                    .ok()
-                    .unwrap();
-            let laws_voting_duration = TryInto::<u64>::try_into(T::LawVotingDuration::get())
-                .ok()
-                .unwrap();
-            let x: f64 =
-                (current_block / (assembly_voting_duration + assembly_election_period)) as f64;
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            // the law is decided by the assembly which proposed it
             ensure!(
-                (x + 1.0) * (assembly_election_period + assembly_election_period) as f64
-                    >= (current_block + laws_voting_duration) as f64,
+                current_block + T::LawVotingDuration::get() <= Self::next_term_start(),
                 <Error<T>>::LifeTimeIsLessThanLaws
             );
             ensure!(
@@ -321,7 +571,8 @@ pub mod pallet {
             T::VotingTrait::create_voting(
                 law_hash,
                 T::LawVotingDuration::get(),
-                Some(<CurrentAssembliesList<T>>::get().len() as u32),
+                VotingRules::all_voters(<CurrentAssembliesList<T>>::get().len() as u32),
+                None,
             )?;
             <Laws<T>>::insert(
                 law_hash,
@@ -330,6 +581,7 @@ pub mod pallet {
                     law_type,
                 },
             );
+            Self::deposit_event(Event::LawProposed(law_hash, law_type));
             Ok(().into())
         }
 
@@ -346,7 +598,7 @@ pub mod pallet {
             );
 
             //this unwrap() is correct
-            let pasport_id = pallet_identity::Pallet::<T>::passport_id(sender).unwrap();
+            let pasport_id = pallet_identity::Pallet::<T>::passport_id(sender.clone()).unwrap();
             let assembly = VotedAssembly {
                 id: pasport_id,
                 estimate,
@@ -365,6 +617,7 @@ pub mod pallet {
             <VotedAssemblies<T>>::mutate(law_hash, |voted_assemblyes| {
                 voted_assemblyes.insert(assembly);
             });
+            Self::deposit_event(Event::LawVoteCast(law_hash, sender, estimate));
             Ok(().into())
         }
 
@@ -379,62 +632,153 @@ pub mod pallet {
                 T::IdentTrait::check_account_identity(sender.clone(), IdentityType::Citizen),
                 <Error<T>>::AccountCannotSupport
             );
-            let assemblies = <CurrentAssembliesList<T>>::get();
             ensure!(
-                assemblies.contains_key(&assembly_id),
+                <CurrentAssembliesList<T>>::get().contains_key(&assembly_id),
                 <Error<T>>::AssemblyNotFound
             );
-            let liber_stake = T::StakingTrait::get_liber_amount(sender.clone());
-            let liber_stake = TryInto::<u64>::try_into(liber_stake).ok().unwrap();
-            if change_power.is_negative() {
-                ensure!(
-                    (-change_power) as u64 <= liber_stake,
-                    <Error<T>>::ChangePowerTooBig
-                );
-            } else {
-                ensure!(
-                    change_power as u64 <= liber_stake,
-                    <Error<T>>::ChangePowerTooBig
-                );
-            }
-            let current_citizen_power = Self::ballots(sender.clone());
-            let current_assembly_power = assemblies.get(&assembly_id).unwrap();
-            let assemblies_res_power;
-            let citizens_res_power;
-            if change_power > 0 {
-                if current_citizen_power + change_power as u64 > liber_stake {
-                    citizens_res_power = liber_stake;
-                    assemblies_res_power = *current_assembly_power;
-                } else {
-                    citizens_res_power = current_citizen_power + change_power.unsigned_abs();
-                    assemblies_res_power = current_assembly_power + change_power.unsigned_abs();
-                }
-            } else if change_power.unsigned_abs() > current_citizen_power {
-                citizens_res_power = 0;
-                assemblies_res_power = *current_assembly_power;
+            //this unwrap() is correct
+            let citizen = pallet_identity::Pallet::<T>::passport_id(sender.clone()).unwrap();
+            let max_support = Self::voting_power(Self::citizen_power(citizen));
+            ensure!(
+                change_power.unsigned_abs() <= max_support,
+                <Error<T>>::ChangePowerTooBig
+            );
+            let support = Self::supports(citizen, &assembly_id);
+            let new_support = if change_power.is_negative() {
+                // the citizen withdraws only the own support of the member
+                support.saturating_sub(change_power.unsigned_abs())
             } else {
-                citizens_res_power = current_citizen_power - change_power.unsigned_abs();
-                assemblies_res_power = current_assembly_power - change_power.unsigned_abs();
-            }
+                let total_support: u64 = <Supports<T>>::iter_prefix_values(citizen).sum();
+                let free_support = max_support.saturating_sub(total_support);
+                support + change_power.unsigned_abs().min(free_support)
+            };
+            Self::set_support(sender, citizen, assembly_id, new_support);
+            Ok(().into())
+        }
 
-            <Ballots<T>>::insert(sender, citizens_res_power);
-            <CurrentAssembliesList<T>>::mutate(|asymblies_storage| {
-                asymblies_storage.insert(assembly_id, assemblies_res_power);
-            });
+        // the citizens approve or decline the law decided by the assembly
+        #[pallet::weight(1)]
+        pub fn override_law(
+            origin: OriginFor<T>,
+            law_hash: T::Hash,
+            state: LawState,
+        ) -> DispatchResultWithPostInfo {
+            T::ReferendumOrigin::ensure_origin(origin)?;
+            ensure!(state != LawState::InProgress, <Error<T>>::InvalidLawState);
+            let law = <Laws<T>>::get(law_hash).ok_or(<Error<T>>::LawNotFound)?;
+            ensure!(
+                law.state != LawState::InProgress,
+                <Error<T>>::LawVotingInProgress
+            );
+            <Laws<T>>::insert(
+                law_hash,
+                Law {
+                    state,
+                    law_type: law.law_type,
+                },
+            );
+            Self::deposit_event(Event::LawOverridden(law_hash, state));
+            Ok(().into())
+        }
+
+        // the voting power of the next ballots and support changes, the supports already
+        // given are kept
+        #[pallet::weight(1)]
+        pub fn set_voting_power(
+            origin: OriginFor<T>,
+            voting_power: VotingPower,
+        ) -> DispatchResultWithPostInfo {
+            T::ReferendumOrigin::ensure_origin(origin)?;
+            <VotingPowerSetting<T>>::put(voting_power);
+            Self::deposit_event(Event::VotingPowerChanged(voting_power));
             Ok(().into())
         }
     }
 
     impl<T: Config> Pallet<T> {
-        fn initialize() {
+        // opens the assembly voting, returns the weight of the stake snapshot
+        fn initialize() -> Result<Weight, pallet_voting::Error<T>> {
             let candidates = <CandidatesList<T>>::get();
-            T::VotingTrait::create_alt_voting_list(
+            T::VotingTrait::create_alt_voting(
                 T::AssemblyVotingHash::get(),
                 T::AssemblyVotingDuration::get(),
                 candidates,
                 T::WinnersAmount::get(),
+                T::AssemblyCountingMethod::get(),
+                VotingRules::open(),
+                T::AssemblyRevealDuration::get(),
+            )?;
+            Ok(Self::take_stake_snapshot())
+        }
+
+        // the weight of opening the assembly or the prime minister voting, the candidates, the
+        // calendar and the powers of the members are read and written besides the voting
+        pub fn voting_start_weight() -> Weight {
+            T::DbWeight::get()
+                .reads_writes(2, 2)
+                .saturating_add(pallet_voting::Pallet::<T>::creation_weight())
+        }
+
+        // before the calendar the terms started in the multiples of the term length, the
+        // calendar of the last of them is seeded so the next term starts on the old schedule,
+        // the upgrade runs before `frame_system` initializes the block, so the block number
+        // is the last block handled by the old schedule
+        fn seed_calendar() -> Weight {
+            if <Calendar<T>>::exists() {
+                return T::DbWeight::get().reads(1);
+            }
+            let term = T::AssemblyElectionPeriod::get() + T::AssemblyVotingDuration::get();
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            // the first term has not started, `next_term_start` is already right
+            if term.is_zero() || block_number < term {
+                return T::DbWeight::get().reads(2);
+            }
+            <Calendar<T>>::put(Self::term_calendar(block_number - block_number % term));
+            T::DbWeight::get().reads_writes(2, 1)
+        }
+
+        // the powers the citizens voted with were kept in `Ballots` before the supports,
+        // the map is removed once
+        fn remove_old_ballots() -> Weight {
+            let module = <T::PalletInfo as frame_support::traits::PalletInfo>::name::<Pallet<T>>()
+                .unwrap_or_default();
+            let mut prefix = Twox128::hash(module.as_bytes()).to_vec();
+            prefix.extend_from_slice(&Twox128::hash(OLD_BALLOTS));
+            let exists = frame_support::sp_io::storage::next_key(&prefix)
+                .map_or(false, |key| key.starts_with(&prefix));
+            if !exists {
+                return T::DbWeight::get().reads(1);
+            }
+            frame_support::storage::migration::remove_storage_prefix(
+                module.as_bytes(),
+                OLD_BALLOTS,
+                &[],
+            );
+            T::DbWeight::get().reads_writes(1, 1)
+        }
+
+        // the first term starts after the first election period
+        pub fn next_term_start() -> T::BlockNumber {
+            <Calendar<T>>::get().map_or(
+                T::AssemblyElectionPeriod::get() + T::AssemblyVotingDuration::get(),
+                |calendar| calendar.term_end,
             )
-            .unwrap();
+        }
+
+        fn term_calendar(term_start: T::BlockNumber) -> ElectionCalendar<T::BlockNumber> {
+            let voting_end = term_start
+                + T::AssemblyVotingDuration::get()
+                + T::AssemblyRevealDuration::get().unwrap_or_default();
+            let prime_min_voting_start = voting_end + T::PrimeMinVotingDelay::get();
+            ElectionCalendar {
+                term_start,
+                voting_end,
+                prime_min_voting_start,
+                prime_min_voting_end: prime_min_voting_start + T::PrimeMinVotingDuration::get(),
+                term_end: term_start
+                    + T::AssemblyElectionPeriod::get()
+                    + T::AssemblyVotingDuration::get(),
+            }
         }
 
         pub fn add_candidate_internal(id: PassportId) -> Result<(), Error<T>> {
@@ -448,6 +792,33 @@ pub mod pallet {
             Ok(())
         }
 
+        // the candidates of the next or the current assembly election with their profiles,
+        // the candidates added without a deposit have no profile, JSON maps need string keys,
+        // so the candidates are returned as the list of the (candidate, profile) pairs
+        pub fn get_candidates() -> Vec<(
+            Candidate,
+            Option<CandidateProfile<T::AccountId, BalanceOf<T>, T::Hash>>,
+        )> {
+            <CandidatesList<T>>::get()
+                .into_iter()
+                .map(|candidate| {
+                    let profile = <CandidateProfiles<T>>::get(&candidate);
+                    (candidate, profile)
+                })
+                .collect()
+        }
+
+        // the (member, power) pairs, like the candidates
+        pub fn get_current_assemblies() -> Vec<(Candidate, u64)> {
+            <CurrentAssembliesList<T>>::get().into_iter().collect()
+        }
+
+        pub fn get_laws(state: LawState) -> BTreeMap<T::Hash, Law> {
+            <Laws<T>>::iter()
+                .filter(|(_, law)| law.state == state)
+                .collect()
+        }
+
         pub fn add_prime_min_candidate_internal(id: PassportId) -> Result<(), Error<T>> {
             let candidate = pallet_identity::Pallet::<T>::identities(id);
             if !candidate.contains(&IdentityType::Assembly) {
@@ -459,42 +830,211 @@ pub mod pallet {
             Ok(())
         }
 
-        fn start_prime_min_voting() {
-            //This unwrap() is correct
-            T::VotingTrait::create_alt_voting(
+        fn start_prime_min_voting(mut calendar: ElectionCalendar<T::BlockNumber>) {
+            let powers = <CurrentAssembliesList<T>>::get();
+            let created = T::VotingTrait::create_alt_voting(
                 T::PrimeMinVotingHash::get(),
                 T::PrimeMinVotingDuration::get(),
                 <PrimeMinCandidatesList<T>>::get(),
-                Some(<CurrentAssembliesList<T>>::get().len() as u32),
-            )
-            .unwrap();
+                1,
+                CountingMethod::InstantRunoff,
+                VotingRules::all_voters(powers.len() as u32),
+                None,
+            );
+            match created {
+                Ok(()) => {
+                    // the members vote with the power they have now
+                    <PrimeMinVotingPowers<T>>::put(powers);
+                    Self::deposit_event(Event::PrimeMinVotingStarted);
+                }
+                Err(error) => {
+                    calendar.prime_min_voting_start += One::one();
+                    calendar.prime_min_voting_end += One::one();
+                    <Calendar<T>>::put(calendar);
+                    Self::deposit_event(Event::PrimeMinVotingNotStarted(Self::voting_error(error)));
+                }
+            }
         }
 
-        pub fn alt_vote(
+        fn ensure_can_vote(account_id: T::AccountId) -> Result<PassportId, Error<T>> {
+            ensure!(
+                T::IdentTrait::check_account_identity(account_id.clone(), IdentityType::Citizen),
+                <Error<T>>::AccountCannotVote
+            );
+            //this unwrap() is correct
+            let citizen = pallet_identity::Pallet::<T>::passport_id(account_id.clone()).unwrap();
+            // the ballot cast by a delegate is replaced by the ballot of the citizen
+            ensure!(
+                !<VotedCitizens<T>>::get().contains(&citizen)
+                    || <DelegatedBallots<T>>::contains_key(citizen),
+                <Error<T>>::AlreadyVoted
+            );
+            Ok(citizen)
+        }
+
+        // the weight of the ballots cast for the delegators
+        pub fn delegators_weight(delegators: u32) -> Weight {
+            T::DbWeight::get()
+                .reads_writes(9, 4)
+                .saturating_mul(delegators as Weight)
+        }
+
+        // the ballot of the delegate is cast with the power of the citizens who delegated to
+        // the delegate and did not vote themselves, the ballot of a closer delegate replaces it,
+        // returns the number of the delegators, at most `MaxDelegators`
+        fn vote_for_delegators(delegate: &T::AccountId, ballot: AltVote) -> u32 {
+            let mut voted = <VotedCitizens<T>>::get();
+            let voted_directly = |account: &T::AccountId| {
+                pallet_identity::Pallet::<T>::passport_id(account.clone()).map_or(
+                    false,
+                    |citizen| {
+                        voted.contains(&citizen) && !<DelegatedBallots<T>>::contains_key(citizen)
+                    },
+                )
+            };
+            let delegators = T::VotingTrait::delegated_voters(
+                DelegationTopic::AssemblyElections,
+                delegate,
+                voted_directly,
+            );
+            let number = delegators.len() as u32;
+            for delegator in delegators {
+                if !T::IdentTrait::check_account_identity(delegator.clone(), IdentityType::Citizen)
+                {
+                    continue;
+                }
+                //this unwrap() is correct
+                let citizen = pallet_identity::Pallet::<T>::passport_id(delegator.clone()).unwrap();
+                let delegated = <DelegatedBallots<T>>::get(citizen);
+                // the citizen already voted with one of the accounts
+                if voted.contains(&citizen) && delegated.is_none() {
+                    continue;
+                }
+                // a citizen with several delegating accounts keeps one ballot
+                let account = delegated.unwrap_or(delegator);
+                let power = Self::voting_power(Self::stake_snapshot(citizen));
+                if Self::alt_vote(account.clone(), ballot.clone(), power).is_err() {
+                    continue;
+                }
+                voted.insert(citizen);
+                <DelegatedBallots<T>>::insert(citizen, account);
+            }
+            <VotedCitizens<T>>::put(voted);
+            number
+        }
+
+        // moves the power of the member by the change of the support of the citizen
+        fn set_support(
             account_id: T::AccountId,
-            ballot: AltVote,
-            power: u64,
-        ) -> Result<(), Error<T>> {
-            match T::VotingTrait::alt_vote_list(
-                T::AssemblyVotingHash::get(),
+            citizen: PassportId,
+            member: Candidate,
+            new_support: u64,
+        ) {
+            let support = <Supports<T>>::get(citizen, &member);
+            if new_support.is_zero() {
+                <Supports<T>>::remove(citizen, &member);
+            } else {
+                <Supports<T>>::insert(citizen, &member, new_support);
+            }
+            let power = <CurrentAssembliesList<T>>::mutate(|assemblies| {
+                let power = assemblies.entry(member.clone()).or_default();
+                *power = power.saturating_add(new_support).saturating_sub(support);
+                *power
+            });
+            Self::deposit_event(Event::SupportChanged(
                 account_id,
-                ballot,
+                member,
+                new_support,
                 power,
-            ) {
-                Ok(_) => Ok(()),
-                Err(_) => Err(<Error<T>>::VotingNotFound),
+            ));
+        }
+
+        // the support above the current voting power of the citizen is withdrawn starting
+        // from the largest support
+        fn withdraw_excess_support(account_id: T::AccountId, citizen: PassportId) {
+            let mut supports: Vec<(Candidate, u64)> = <Supports<T>>::iter_prefix(citizen).collect();
+            let total_support: u64 = supports.iter().map(|(_, support)| support).sum();
+            let max_support = Self::voting_power(Self::citizen_power(citizen));
+            let mut excess = total_support.saturating_sub(max_support);
+            supports.sort_by(|a, b| b.1.cmp(&a.1));
+            for (member, support) in supports {
+                if excess.is_zero() {
+                    break;
+                }
+                let withdrawn = support.min(excess);
+                excess -= withdrawn;
+                Self::set_support(account_id.clone(), citizen, member, support - withdrawn);
             }
         }
 
+        // the liber stake of every citizen is fixed when the assembly voting opens, the snapshot
+        // of the last voting is replaced, returns the weight of it
+        fn take_stake_snapshot() -> Weight {
+            let db_weight = T::DbWeight::get();
+            let removed = <StakeSnapshot<T>>::drain().count() as Weight;
+            let mut weight = db_weight.reads_writes(removed, removed);
+            pallet_identity::Identities::<T>::iter().for_each(|(citizen, identities)| {
+                weight = weight.saturating_add(db_weight.reads(1));
+                if !identities.contains(&IdentityType::Citizen) {
+                    return;
+                }
+                let accounts = pallet_identity::Pallet::<T>::account_ids(citizen);
+                weight = weight.saturating_add(Self::stake_weight(accounts.len() as u32));
+                let stake = Self::accounts_power(&accounts);
+                if !stake.is_zero() {
+                    <StakeSnapshot<T>>::insert(citizen, stake);
+                    weight = weight.saturating_add(db_weight.writes(1));
+                }
+            });
+            weight
+        }
+
+        // the accounts of the citizen are read, then the bond and the ledger of every account
+        fn stake_weight(accounts: u32) -> Weight {
+            let reads = (accounts as Weight).saturating_mul(2).saturating_add(1);
+            T::DbWeight::get().reads(reads)
+        }
+
+        // the liber stake of all accounts of the citizen
+        fn citizen_power(citizen: PassportId) -> u64 {
+            Self::accounts_power(&pallet_identity::Pallet::<T>::account_ids(citizen))
+        }
+
+        fn accounts_power(accounts: &BTreeSet<T::AccountId>) -> u64 {
+            let mut power: pallet_staking::BalanceOf<T> = Zero::zero();
+            accounts.iter().for_each(|account_id| {
+                power += T::StakingTrait::get_liber_amount(account_id.clone());
+            });
+            TryInto::<u64>::try_into(power).unwrap_or(u64::MAX)
+        }
+
+        fn voting_power(liber_stake: u64) -> u64 {
+            <VotingPowerSetting<T>>::get()
+                .unwrap_or_else(T::AssemblyVotingPower::get)
+                .power(liber_stake)
+        }
+
+        pub fn alt_vote(account_id: T::AccountId, ballot: AltVote, power: u64) -> DispatchResult {
+            T::VotingTrait::alt_vote(T::AssemblyVotingHash::get(), account_id, ballot, power)
+                .map_err(Self::voting_error)
+        }
+
         pub fn prime_min_alt_vote(
             account_id: T::AccountId,
             ballot: AltVote,
             power: u64,
-        ) -> Result<(), Error<T>> {
-            match T::VotingTrait::alt_vote(T::PrimeMinVotingHash::get(), account_id, ballot, power)
-            {
-                Ok(_) => Ok(()),
-                Err(_) => Err(<Error<T>>::VotingNotFound),
+        ) -> DispatchResult {
+            T::VotingTrait::alt_vote(T::PrimeMinVotingHash::get(), account_id, ballot, power)
+                .map_err(Self::voting_error)
+        }
+
+        // invalid ballots keep the error of the voting
+        fn voting_error(error: pallet_voting::Error<T>) -> DispatchError {
+            match error {
+                pallet_voting::Error::VotingSubjectDoesNotExist => {
+                    <Error<T>>::VotingNotFound.into()
+                }
+                error => error.into(),
             }
         }
 
@@ -505,16 +1045,42 @@ pub mod pallet {
         }
     }
 
-    #[allow(clippy::type_complexity)]
-    impl<T: Config> pallet_voting::finalize_voiting_trait::FinalizeAltVotingListDispatchTrait<T>
-        for Pallet<T>
-    {
+    // the support above the remaining liber stake of the citizen is withdrawn starting from
+    // the largest support
+    impl<T: Config> pallet_staking::OnLiberStakeChanged<T::AccountId> for Pallet<T> {
+        fn on_liber_stake_changed(stash: &T::AccountId) {
+            if let Some(citizen) = pallet_identity::Pallet::<T>::passport_id(stash.clone()) {
+                Self::withdraw_excess_support(stash.clone(), citizen);
+            }
+        }
+    }
+
+    // the laws of the assembly for the other pallets
+    impl<T: Config> Contains<T::Hash> for Pallet<T> {
+        fn contains(law_hash: &T::Hash) -> bool {
+            <Laws<T>>::contains_key(law_hash)
+        }
+    }
+
+    impl<T: Config> pallet_voting::FinalizeVotingDispatchTrait<T> for Pallet<T> {
         fn finalize_voting(
-            _subject: T::Hash,
-            _voting_settings: pallet_voting::AltVotingListSettings<T::BlockNumber>,
-            winners: BTreeMap<Candidate, u64>,
-            ballots_storage: BTreeMap<T::Hash, BTreeMap<T::AccountId, (AltVote, u64)>>,
+            subject: T::Hash,
+            voting_settings: VotingSettings<T::BlockNumber>,
+            result: CountResult,
+            ballots: &BallotsView<T>,
         ) {
+            if subject == T::AssemblyVotingHash::get() {
+                Self::finalize_assembly_voting(result.winners, ballots);
+            } else if subject == T::PrimeMinVotingHash::get() {
+                Self::finalize_prime_min_voting(result.winners);
+            } else {
+                Self::finalize_law_voting(subject, voting_settings);
+            }
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        fn finalize_assembly_voting(winners: BTreeMap<Candidate, u64>, ballots: &BallotsView<T>) {
             <CurrentAssembliesList<T>>::get()
                 .iter()
                 .for_each(|assembly| {
@@ -524,39 +1090,85 @@ pub mod pallet {
                     );
                 });
 
-            <Ballots<T>>::remove_all();
+            <Supports<T>>::remove_all();
             <AssemblyStakeAmount<T>>::kill();
             <CurrentAssembliesList<T>>::kill();
+            Self::settle_candidate_deposits(&winners, ballots);
             <CandidatesList<T>>::kill();
             <VotedCitizens<T>>::kill();
+            <DelegatedBallots<T>>::remove_all();
 
-            <CurrentAssembliesList<T>>::mutate(|e| {
-                for (id, power) in winners.iter() {
-                    T::IdentTrait::push_identity(
-                        Self::vec_u8_to_pasport_id(id),
-                        IdentityType::Assembly,
-                    )
-                    .unwrap();
-                    e.insert(id.clone(), *power);
+            for id in winners.keys() {
+                T::IdentTrait::push_identity(
+                    Self::vec_u8_to_pasport_id(id),
+                    IdentityType::Assembly,
+                )
+                .unwrap();
+            }
+
+            // the citizens support the first elected candidate of their ballots, the power of
+            // the members is the sum of their supports and not their tallies, which the count
+            // could have moved by the surplus transfers
+            let mut powers: BTreeMap<Candidate, u64> =
+                winners.keys().map(|member| (member.clone(), 0)).collect();
+            let mut supporters = BTreeMap::new();
+            ballots.iter().for_each(|(voter, (ballot, power))| {
+                let citizen = match pallet_identity::Pallet::<T>::passport_id(voter.clone()) {
+                    Some(citizen) => citizen,
+                    None => return,
+                };
+                if let Some(member) = ballot.content.iter().find(|c| powers.contains_key(*c)) {
+                    <Supports<T>>::mutate(citizen, member, |support| {
+                        *support = support.saturating_add(power)
+                    });
+                    powers.entry(member.clone()).and_modify(|member_power| {
+                        *member_power = member_power.saturating_add(power)
+                    });
+                    supporters.insert(citizen, voter);
                 }
             });
+            <CurrentAssembliesList<T>>::put(powers);
+            // the ballots were cast with the snapshot, the stake or the voting power could
+            // have changed since
+            for (citizen, voter) in supporters {
+                Self::withdraw_excess_support(voter, citizen);
+            }
+            <VotingState<T>>::mutate(|state| *state = false);
+            Self::deposit_event(Event::AssemblyElected(<CurrentAssembliesList<T>>::get()));
+        }
 
-            ballots_storage.iter().for_each(|storage| {
-                storage.1.iter().for_each(|map| {
-                    let alt = map.1;
-                    <Ballots<T>>::insert(map.0, alt.1);
-                });
+        // the deposits of the candidates who were elected or got at least
+        // `CandidateDepositThreshold` of the first preferences are returned, the rest is burned
+        fn settle_candidate_deposits(winners: &BTreeMap<Candidate, u64>, ballots: &BallotsView<T>) {
+            let mut first_preferences: BTreeMap<Candidate, u64> = BTreeMap::new();
+            let mut total: u64 = 0;
+            ballots.iter().for_each(|(_, (ballot, power))| {
+                total = total.saturating_add(power);
+                if let Some(candidate) = ballot.content.front() {
+                    let votes = first_preferences.entry(candidate.clone()).or_default();
+                    *votes = votes.saturating_add(power);
+                }
+            });
+            let threshold = T::CandidateDepositThreshold::get().mul_ceil(total);
+            <CandidateProfiles<T>>::drain().for_each(|(candidate, profile)| {
+                let votes = first_preferences
+                    .get(&candidate)
+                    .copied()
+                    .unwrap_or_default();
+                if winners.contains_key(&candidate) || votes >= threshold {
+                    T::DepositCurrency::unreserve(&profile.depositor, profile.deposit);
+                } else {
+                    let _ = T::DepositCurrency::slash_reserved(&profile.depositor, profile.deposit);
+                }
             });
-            <VotingState<T>>::mutate(|state| *state = false);
         }
-    }
 
-    impl<T: Config> pallet_voting::FinilizeAltVotingDispatchTrait<T> for Pallet<T> {
-        fn finalize_voting(
-            _subject: T::Hash,
-            _voting_setting: AltVoutingSettings<T::BlockNumber>,
-            winner: Candidate,
-        ) {
+        fn finalize_prime_min_voting(winners: BTreeMap<Candidate, u64>) {
+            // the voting is not decided if no ballot names a candidate
+            let winner = match winners.into_iter().find(|(_, votes)| *votes > 0) {
+                Some((winner, _)) => winner,
+                None => return,
+            };
             if let Some(prime) = <CurrentPrimeMinister<T>>::get() {
                 T::IdentTrait::remove_identity(
                     Self::vec_u8_to_pasport_id(&prime),
@@ -572,62 +1184,83 @@ pub mod pallet {
             .unwrap();
             <PrimeMinCandidatesList<T>>::kill();
             <VotedForPrimeMinAssemblies<T>>::kill();
+            Self::deposit_event(Event::PrimeMinisterElected(winner));
         }
-    }
 
-    impl<T: Config> pallet_voting::FinalizeVotingDispatchTrait<T> for Pallet<T> {
-        fn finalize_voting(
-            subject: T::Hash,
-            voting_setting: pallet_voting::VotingSettings<T::BlockNumber>,
-        ) {
+        fn finalize_law_voting(subject: T::Hash, voting_setting: VotingSettings<T::BlockNumber>) {
             let total_power: u64 = <CurrentAssembliesList<T>>::get().iter().map(|e| e.1).sum();
             <AssemblyStakeAmount<T>>::mutate(|value| *value = total_power);
             if let Some(law) = <Laws<T>>::get(subject) {
-                match law.law_type {
-                    LawType::ConstitutionalChange | LawType::Legislation => {
-                        if ((voting_setting.result as f64 / total_power as f64) * 100.0) > 66.6 {
-                            <Laws<T>>::insert(
-                                subject,
-                                Law {
-                                    state: LawState::Approved,
-                                    law_type: law.law_type,
-                                },
-                            );
-                        } else {
-                            <Laws<T>>::insert(
-                                subject,
-                                Law {
-                                    state: LawState::Declined,
-                                    law_type: law.law_type,
-                                },
-                            );
-                        }
-                    }
-                    _ => {
-                        if ((voting_setting.result as f64 / total_power as f64) * 100.0) > 50.0 {
-                            <Laws<T>>::insert(
-                                subject,
-                                Law {
-                                    state: LawState::Approved,
-                                    law_type: law.law_type,
-                                },
-                            );
-                        } else {
-                            <Laws<T>>::insert(
-                                subject,
-                                Law {
-                                    state: LawState::Declined,
-                                    law_type: law.law_type,
-                                },
-                            );
-                        }
-                    }
-                }
+                let approval = match law.law_type {
+                    LawType::ConstitutionalChange | LawType::Legislation => 66.6,
+                    _ => 50.0,
+                };
+                let state = if voting_setting.quorum_reached()
+                    && ((voting_setting.result as f64 / total_power as f64) * 100.0) > approval
+                {
+                    LawState::Approved
+                } else {
+                    LawState::Declined
+                };
+                <Laws<T>>::insert(
+                    subject,
+                    Law {
+                        state,
+                        law_type: law.law_type,
+                    },
+                );
+                Self::deposit_event(Event::LawDecided(subject, state));
             }
         }
     }
 }
 
+sp_api::decl_runtime_apis! {
+    pub trait AssemblyPalletApi<T: Config> {
+        fn get_candidates(
+        ) -> Vec<(Candidate, Option<CandidateProfile<T::AccountId, BalanceOf<T>, T::Hash>>)>;
+
+        fn get_election_calendar() -> Option<ElectionCalendar<T::BlockNumber>>;
+
+        // the members of the assembly with their power
+        fn get_current_assemblies() -> Vec<(Candidate, u64)>;
+
+        fn get_laws(state: LawState) -> BTreeMap<T::Hash, Law>;
+
+        fn get_current_prime_minister() -> Option<Candidate>;
+
+        fn get_prime_min_candidates() -> BTreeSet<Candidate>;
+
+        // the block the next assembly voting starts in
+        fn get_next_election() -> T::BlockNumber;
+    }
+}
+
+// the blocks of a term of the assembly, the term starts with the assembly voting and the next
+// term starts in `term_end`
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Debug, Eq, PartialEq)]
+pub struct ElectionCalendar<BlockNumber> {
+    pub term_start: BlockNumber,
+    // the last block of the assembly voting, including the reveal window
+    pub voting_end: BlockNumber,
+    // the prime minister voting starts `PrimeMinVotingDelay` blocks after the assembly voting
+    pub prime_min_voting_start: BlockNumber,
+    pub prime_min_voting_end: BlockNumber,
+    pub term_end: BlockNumber,
+}
+
+// a candidate of the assembly election as the candidate registered
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Encode, Decode, Debug, Eq, PartialEq)]
+pub struct CandidateProfile<AccountId, Balance, Hash> {
+    pub depositor: AccountId,
+    pub deposit: Balance,
+    pub display_name: Vec<u8>,
+    // the hash of the manifesto, which is kept off the chain
+    pub manifesto: Hash,
+}
+
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Encode, Decode, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum LawState {
@@ -664,3 +1297,27 @@ pub struct VotedAssembly {
     pub id: PassportId,
     pub estimate: Decision,
 }
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Debug, Eq, PartialEq)]
+pub enum VotingPower {
+    // the power is the liber stake
+    Linear,
+    // the power is the integer square root of the liber stake
+    SquareRoot,
+    // the power is the liber stake up to the cap
+    CappedLinear(u64),
+    // every citizen has the power of one
+    OnePersonOneVote,
+}
+
+impl VotingPower {
+    pub fn power(&self, liber_stake: u64) -> u64 {
+        match self {
+            Self::Linear => liber_stake,
+            Self::SquareRoot => liber_stake.integer_sqrt(),
+            Self::CappedLinear(cap) => liber_stake.min(*cap),
+            Self::OnePersonOneVote => 1,
+        }
+    }
+}
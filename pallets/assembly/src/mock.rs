@@ -8,13 +8,15 @@ use frame_support::{
 };
 
 use frame_election_provider_support::onchain;
+use pallet_assembly::VotingPower;
 use pallet_staking::{ConvertCurve, EraIndex, Module, NegativeImbalanceOf, StakerStatus};
+use pallet_voting::CountingMethod;
 use sp_core::H256;
 use sp_runtime::{
     curve::PiecewiseLinear,
     testing::{Header, TestXt, UintAuthorityId},
     traits::IdentityLookup,
-    Perbill,
+    Perbill, Percent,
 };
 use sp_staking::SessionIndex;
 
@@ -76,9 +78,9 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-        AssemblyPallet: pallet_assembly::{Pallet,Call,Storage},
+        AssemblyPallet: pallet_assembly::{Pallet, Call, Storage, Event<T>},
         IdentityPallet: pallet_identity::{Pallet,Call,Storage},
-        VotingPallet: pallet_voting::{Pallet,Call,Storage},
+        VotingPallet: pallet_voting::{Pallet, Call, Storage, Event<T>},
         Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         Staking: pallet_staking::{Pallet, Call, Config<T>, Storage, Event<T>},
@@ -242,6 +244,7 @@ impl pallet_staking::Config for Test {
     type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
     type ElectionProvider = onchain::OnChainSequentialPhragmen<Self>;
     type WeightInfo = ();
+    type OnLiberStakeChanged = AssemblyPallet;
 }
 
 impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
@@ -287,32 +290,57 @@ parameter_types! {
     pub const LawVotingDuration: u64 = 60 * 1000 / 6000;
     pub const AssemblyVotingHash: H256 = sp_core::H256::zero();
     pub const WinnersAmount: u32 = 3;
+    pub static AssemblyCountingMethod: CountingMethod = CountingMethod::InstantRunoff;
+    pub static AssemblyVotingPower: VotingPower = VotingPower::Linear;
+    pub static AssemblyRevealDuration: Option<u64> = None;
     pub const PrimeMinVotingDuration: u32 = 2 * 60 * 1000 / 6000;
     pub const PrimeMinVotingHash: H256 = sp_core::H256::repeat_byte(1);
     pub const PrimeMinVotingDelay: u64 = 10;
+    pub const CandidateDeposit: Balance = 5;
+    pub const CandidateDepositThreshold: Percent = Percent::from_percent(20);
+    pub const MaxDisplayNameLength: u32 = 16;
 }
 impl pallet_assembly::Config for Test {
+    type Event = Event;
     type AssemblyElectionPeriod = AssemblyElectionPeriod;
     type AssemblyVotingHash = AssemblyVotingHash;
     type AssemblyVotingDuration = AssemblyVotingDuration;
     type LawVotingDuration = LawVotingDuration;
     type WinnersAmount = WinnersAmount;
+    type AssemblyCountingMethod = AssemblyCountingMethod;
+    type AssemblyVotingPower = AssemblyVotingPower;
+    type AssemblyRevealDuration = AssemblyRevealDuration;
     type IdentTrait = IdentityPallet;
     type VotingTrait = VotingPallet;
     type StakingTrait = Staking;
     type PrimeMinVotingDuration = PrimeMinVotingDuration;
     type PrimeMinVotingHash = PrimeMinVotingHash;
     type PrimeMinVotingDelay = PrimeMinVotingDelay;
+    type DepositCurrency = Balances;
+    type CandidateDeposit = CandidateDeposit;
+    type CandidateDepositThreshold = CandidateDepositThreshold;
+    type MaxDisplayNameLength = MaxDisplayNameLength;
+    type ReferendumOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 impl pallet_identity::Config for Test {}
 
+parameter_types! {
+    pub const MaxPreferences: u32 = 16;
+    pub const MaxCandidateLength: u32 = 32;
+    pub const MaxFinalizationsPerBlock: u32 = 10;
+    pub const MaxBallotRemovalsPerBlock: u32 = 100;
+    pub const MaxDelegators: u32 = 8;
+}
+
 impl pallet_voting::Config for Test {
+    type Event = Event;
     type FinalizeVotingDispatch = AssemblyPallet;
-
-    type FinalizeAltVotingDispatch = AssemblyPallet;
-
-    type FinalizeAltVotingListDispatch = AssemblyPallet;
+    type MaxPreferences = MaxPreferences;
+    type MaxCandidateLength = MaxCandidateLength;
+    type MaxFinalizationsPerBlock = MaxFinalizationsPerBlock;
+    type MaxBallotRemovalsPerBlock = MaxBallotRemovalsPerBlock;
+    type MaxDelegators = MaxDelegators;
 }
 
 impl ExtBuilder {
@@ -4,11 +4,14 @@ use crate::mock::*;
 use crate::*;
 use frame_support::{
     assert_err, assert_ok,
-    traits::{OnFinalize, OnInitialize},
+    traits::{OnFinalize, OnInitialize, OnRuntimeUpgrade},
+    StorageValue,
 };
 use frame_system::ensure_signed;
 use pallet_staking::RewardDestination;
+use sp_core::H256;
 use sp_runtime::traits::Hash;
+use sp_runtime::DispatchError;
 
 #[test]
 fn basic_assembly_test() {
@@ -178,6 +181,8 @@ fn basic_assembly_test() {
                 .collect()
         );
 
+        // the next voting is created after the block of the ballots which are being removed
+        System::set_block_number(70 + 30);
         AssemblyPallet::on_initialize(70 + 30);
         // Change power test
 
@@ -281,6 +286,7 @@ fn basic_assembly_test() {
         AssemblyPallet::add_candidate_internal(id4).unwrap();
         AssemblyPallet::add_candidate_internal(id5).unwrap();
 
+        System::set_block_number(120 + 30);
         AssemblyPallet::on_initialize(120 + 30);
 
         AssemblyPallet::vote(account6.clone(), ballot_1.clone()).unwrap();
@@ -336,6 +342,7 @@ fn basic_assembly_test() {
         AssemblyPallet::add_candidate_internal(id4).unwrap();
         AssemblyPallet::add_candidate_internal(id5).unwrap();
 
+        System::set_block_number(170 + 30);
         AssemblyPallet::on_initialize(170 + 30);
 
         AssemblyPallet::vote(account6.clone(), ballot_1.clone()).unwrap();
@@ -407,9 +414,15 @@ fn assembly_errorss_test() {
 
         AssemblyPallet::on_initialize(50);
 
+        // only 1 is registered as a candidate
+        assert_err!(
+            AssemblyPallet::vote(account2.clone(), ballot_1.clone()),
+            pallet_voting::Error::<Test>::UnknownCandidate
+        );
+        let ballot_1 = pallet_voting::AltVote::new(VecDeque::from(vec![[1_u8; 32].to_vec()]));
         assert_ok!(AssemblyPallet::vote(account2.clone(), ballot_1.clone()));
         assert_err!(
-            AssemblyPallet::add_candidate(account2.clone()),
+            AssemblyPallet::add_candidate(account2.clone(), b"two".to_vec(), Default::default()),
             <Error<Test>>::VotingIsAlreadyInProgress
         );
         assert_err!(
@@ -1012,3 +1025,967 @@ fn test_accelerated_alt_voting() {
         );
     });
 }
+
+#[test]
+fn secret_assembly_voting_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        AssemblyRevealDuration::set(Some(10));
+        for id in 1..5 {
+            IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+            AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+        }
+        for (account, stake) in [(6, 1), (7, 1), (8, 3), (9, 1)] {
+            Staking::liberland_bond(
+                Origin::signed(account),
+                account,
+                stake,
+                RewardDestination::Controller,
+            )
+            .unwrap();
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        AssemblyPallet::on_initialize(50);
+
+        let salt = b"salt".to_vec();
+        let ballots = [(6, 1_u8), (7, 2), (8, 4), (9, 3)];
+        for (account, candidate) in ballots {
+            let ballot =
+                pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]));
+            assert_err!(
+                AssemblyPallet::vote(Origin::signed(account), ballot.clone()),
+                pallet_voting::Error::<Test>::SecretVoting
+            );
+            assert_ok!(AssemblyPallet::commit_vote(
+                Origin::signed(account),
+                VotingPallet::commitment_of(&account, AssemblyVotingHash::get(), &ballot, &salt)
+            ));
+        }
+        assert_err!(
+            AssemblyPallet::commit_vote(Origin::signed(6), Default::default()),
+            <Error<Test>>::AlreadyVoted
+        );
+
+        // the voting is submitted at block 1, the reveal window is 22..=31
+        System::set_block_number(22);
+        for (account, candidate) in [(6, 1_u8), (7, 2), (9, 3)] {
+            let ballot =
+                pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]));
+            assert_ok!(AssemblyPallet::reveal_vote(
+                Origin::signed(account),
+                ballot,
+                salt.clone()
+            ));
+        }
+        VotingPallet::on_finalize(30);
+        assert_eq!(AssemblyPallet::voting_state(), true);
+        VotingPallet::on_finalize(31);
+
+        // the unrevealed ballot of 8 is excluded, so 4 is not elected
+        let mut winners = BTreeMap::new();
+        winners.insert([1_u8; 32].to_vec(), 1);
+        winners.insert([2_u8; 32].to_vec(), 1);
+        winners.insert([3_u8; 32].to_vec(), 1);
+        assert_eq!(AssemblyPallet::ministers_list(), winners);
+        assert_eq!(AssemblyPallet::supports([6; 32], [1_u8; 32].to_vec()), 1);
+        assert_eq!(AssemblyPallet::supports([8; 32], [4_u8; 32].to_vec()), 0);
+    });
+}
+
+#[test]
+fn delegated_assembly_voting_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        for id in 1..5 {
+            IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+            AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+        }
+        for (account, stake) in [(6, 1), (7, 1), (8, 3), (9, 1)] {
+            Staking::liberland_bond(
+                Origin::signed(account),
+                account,
+                stake,
+                RewardDestination::Controller,
+            )
+            .unwrap();
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        assert_err!(
+            AssemblyPallet::delegate_vote(Origin::signed(6), 20),
+            <Error<Test>>::DelegateIsNotCitizen
+        );
+        // 7 -> 6 -> 8
+        assert_ok!(AssemblyPallet::delegate_vote(Origin::signed(6), 8));
+        assert_ok!(AssemblyPallet::delegate_vote(Origin::signed(7), 6));
+        AssemblyPallet::on_initialize(50);
+
+        let ballot = |candidate: u8| {
+            pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]))
+        };
+        // 8 votes for 6 and 7 with their power, the unused weight is refunded
+        let post_info = AssemblyPallet::vote(Origin::signed(8), ballot(4)).unwrap();
+        assert_eq!(
+            post_info.actual_weight,
+            Some(1 + AssemblyPallet::delegators_weight(2))
+        );
+        let post_info = AssemblyPallet::vote(Origin::signed(9), ballot(3)).unwrap();
+        assert_eq!(post_info.actual_weight, Some(1));
+        // the ballot of 6 replaces the ballot of 8 for 6 and 7
+        assert_ok!(AssemblyPallet::vote(Origin::signed(6), ballot(1)));
+        assert_err!(
+            AssemblyPallet::vote(Origin::signed(6), ballot(2)),
+            <Error<Test>>::AlreadyVoted
+        );
+        VotingPallet::on_finalize(21);
+
+        let mut winners = BTreeMap::new();
+        winners.insert([1_u8; 32].to_vec(), 2);
+        winners.insert([3_u8; 32].to_vec(), 1);
+        winners.insert([4_u8; 32].to_vec(), 3);
+        assert_eq!(AssemblyPallet::ministers_list(), winners);
+        assert_eq!(AssemblyPallet::supports([7; 32], [1_u8; 32].to_vec()), 1);
+    });
+}
+
+#[test]
+fn delegated_ballot_of_linked_accounts_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        for id in 1..5 {
+            IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+            AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+        }
+        // 6 and 16 are the accounts of the citizen 6
+        for (account, citizen, stake) in [(6, 6, 1), (16, 6, 2), (8, 8, 3)] {
+            Staking::liberland_bond(
+                Origin::signed(account),
+                account,
+                stake,
+                RewardDestination::Controller,
+            )
+            .unwrap();
+            IdentityPallet::match_account_to_id(account, [citizen; 32]);
+        }
+        IdentityPallet::push_identity([6; 32], IdentityType::Citizen).unwrap();
+        IdentityPallet::push_identity([8; 32], IdentityType::Citizen).unwrap();
+        assert_ok!(AssemblyPallet::delegate_vote(Origin::signed(6), 8));
+        assert_ok!(AssemblyPallet::delegate_vote(Origin::signed(16), 8));
+        AssemblyPallet::on_initialize(50);
+
+        let ballot = |candidate: u8| {
+            pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]))
+        };
+        let ballots = || {
+            let voting = VotingPallet::active_votings(AssemblyVotingHash::get()).unwrap();
+            let mut ballots: Vec<_> =
+                VotingPallet::subject_ballots(AssemblyVotingHash::get(), voting.submitted_height)
+                    .map(|(_, ballot)| ballot)
+                    .collect();
+            ballots.sort_by_key(|(ballot, _)| ballot.clone());
+            ballots
+        };
+        // the citizen is represented once for both accounts
+        assert_ok!(AssemblyPallet::vote(Origin::signed(8), ballot(4)));
+        assert_eq!(ballots(), vec![(ballot(4), 3), (ballot(4), 3)]);
+        // any account of the citizen replaces the delegated ballot
+        assert_ok!(AssemblyPallet::vote(Origin::signed(16), ballot(1)));
+        assert_eq!(ballots(), vec![(ballot(1), 3), (ballot(4), 3)]);
+        for account in [6, 16] {
+            assert_err!(
+                AssemblyPallet::vote(Origin::signed(account), ballot(2)),
+                <Error<Test>>::AlreadyVoted
+            );
+        }
+    });
+}
+
+#[test]
+fn candidate_registration_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        for account in [1, 2, 3, 4, 5, 10] {
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        let free_balance = |account: u64| Balances::free_balance(account);
+        let initial_balances: Vec<_> = (1..=4).map(free_balance).collect();
+        for account in 1..=4 {
+            assert_ok!(AssemblyPallet::add_candidate(
+                Origin::signed(account),
+                vec![account as u8],
+                H256::repeat_byte(account as u8)
+            ));
+        }
+        AssemblyPallet::add_candidate_internal([5; 32]).unwrap();
+        assert_err!(
+            AssemblyPallet::add_candidate(Origin::signed(3), vec![3], Default::default()),
+            <Error<Test>>::AlreadyCandidate
+        );
+        assert_err!(
+            AssemblyPallet::add_candidate(Origin::signed(10), vec![0; 17], Default::default()),
+            <Error<Test>>::DisplayNameTooLong
+        );
+        assert_err!(
+            AssemblyPallet::add_candidate(Origin::signed(10), vec![10], Default::default()),
+            <Error<Test>>::InsufficientDeposit
+        );
+        assert_eq!(Balances::reserved_balance(1), CandidateDeposit::get());
+
+        // the deposit is returned on the withdrawal
+        assert_ok!(AssemblyPallet::withdraw_candidacy(Origin::signed(4)));
+        assert_eq!(Balances::reserved_balance(4), 0);
+        assert_err!(
+            AssemblyPallet::withdraw_candidacy(Origin::signed(4)),
+            <Error<Test>>::NotCandidate
+        );
+
+        let candidates: BTreeMap<_, _> = AssemblyPallet::get_candidates().into_iter().collect();
+        assert_eq!(candidates.len(), 4);
+        assert_eq!(candidates[&[5_u8; 32].to_vec()], None);
+        let profile = candidates[&[3_u8; 32].to_vec()].clone().unwrap();
+        assert_eq!(
+            (profile.depositor, profile.display_name, profile.manifesto),
+            (3, vec![3], H256::repeat_byte(3))
+        );
+
+        for (account, stake) in [(6, 1), (7, 1), (8, 3), (9, 1)] {
+            Staking::liberland_bond(
+                Origin::signed(account),
+                account,
+                stake,
+                RewardDestination::Controller,
+            )
+            .unwrap();
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        AssemblyPallet::on_initialize(50);
+        assert_err!(
+            AssemblyPallet::withdraw_candidacy(Origin::signed(1)),
+            <Error<Test>>::VotingIsAlreadyInProgress
+        );
+        for (account, candidate) in [(8, 1_u8), (6, 2), (7, 5), (9, 5)] {
+            let ballot =
+                pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]));
+            assert_ok!(AssemblyPallet::vote(Origin::signed(account), ballot));
+        }
+        VotingPallet::on_finalize(21);
+
+        // 2 is elected with less than 20% of the first preferences, 3 has none
+        let balances: Vec<_> = (1..=4).map(free_balance).collect();
+        assert_eq!(
+            balances,
+            vec![
+                initial_balances[0],
+                initial_balances[1],
+                initial_balances[2] - CandidateDeposit::get(),
+                initial_balances[3]
+            ]
+        );
+        assert!((1..=4).all(|account: u64| Balances::reserved_balance(account) == 0));
+        assert!(AssemblyPallet::get_candidates().is_empty());
+    });
+}
+
+#[test]
+fn election_calendar_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        IdentityPallet::push_identity([1; 32], IdentityType::Citizen).unwrap();
+        let term = AssemblyElectionPeriod::get() + AssemblyVotingDuration::get();
+        let terms = 6;
+        let mut assembly_votings = vec![];
+        let mut prime_min_votings = vec![];
+        let mut calendars = vec![];
+
+        for block in 1..=term * terms + 1 {
+            if block % term == 0 {
+                AssemblyPallet::add_candidate_internal([1; 32]).unwrap();
+            }
+            System::set_block_number(block);
+            let voting_before = VotingPallet::active_votings(AssemblyVotingHash::get());
+            let prime_min_before = VotingPallet::active_votings(PrimeMinVotingHash::get());
+            AssemblyPallet::on_initialize(block);
+            VotingPallet::on_finalize(block);
+            AssemblyPallet::on_finalize(block);
+            if voting_before.is_none()
+                && VotingPallet::active_votings(AssemblyVotingHash::get()).is_some()
+            {
+                assembly_votings.push(block);
+                calendars.push(AssemblyPallet::election_calendar().unwrap());
+            }
+            if prime_min_before.is_none()
+                && VotingPallet::active_votings(PrimeMinVotingHash::get()).is_some()
+            {
+                prime_min_votings.push(block);
+            }
+        }
+
+        let starts: Vec<u64> = (1..=terms).map(|n| n * term).collect();
+        assert_eq!(assembly_votings, starts);
+        // 20 blocks of the assembly voting and 10 blocks of the delay in every term
+        let prime_min_starts: Vec<u64> = starts.iter().map(|start| start + 30).collect();
+        assert_eq!(prime_min_votings, prime_min_starts);
+        for (calendar, start) in calendars.iter().zip(starts) {
+            assert_eq!(
+                *calendar,
+                ElectionCalendar {
+                    term_start: start,
+                    voting_end: start + 20,
+                    prime_min_voting_start: start + 30,
+                    prime_min_voting_end: start + 50,
+                    term_end: start + term,
+                }
+            );
+        }
+        assert_eq!(AssemblyPallet::next_term_start(), term * (terms + 1));
+    });
+}
+
+#[test]
+fn calendar_migration_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        let term = AssemblyElectionPeriod::get() + AssemblyVotingDuration::get();
+
+        // the first term has not started
+        System::set_block_number(term - 1);
+        AssemblyPallet::on_runtime_upgrade();
+        assert_eq!(AssemblyPallet::election_calendar(), None);
+        assert_eq!(AssemblyPallet::next_term_start(), term);
+
+        // the second term started under the old schedule
+        System::set_block_number(2 * term + 5);
+        AssemblyPallet::on_runtime_upgrade();
+        let calendar = AssemblyPallet::election_calendar().unwrap();
+        assert_eq!(calendar.term_start, 2 * term);
+        assert_eq!(AssemblyPallet::next_term_start(), 3 * term);
+
+        // the seeded calendar is kept
+        System::set_block_number(4 * term);
+        AssemblyPallet::on_runtime_upgrade();
+        assert_eq!(AssemblyPallet::election_calendar(), Some(calendar));
+
+        IdentityPallet::push_identity([1; 32], IdentityType::Citizen).unwrap();
+        AssemblyPallet::add_candidate_internal([1; 32]).unwrap();
+        System::set_block_number(3 * term);
+        AssemblyPallet::on_initialize(3 * term);
+        assert!(VotingPallet::active_votings(AssemblyVotingHash::get()).is_some());
+        assert_eq!(
+            AssemblyPallet::election_calendar().unwrap().term_start,
+            3 * term
+        );
+    });
+}
+
+#[test]
+fn voting_start_retry_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        IdentityPallet::push_identity([1; 32], IdentityType::Citizen).unwrap();
+        AssemblyPallet::add_candidate_internal([1; 32]).unwrap();
+        let db_weight = frame_support::weights::constants::RocksDbWeight::get();
+        let fill = |subjects: std::ops::Range<u64>, duration: u64| {
+            for subject in subjects {
+                VotingPallet::create_voting(
+                    H256::from_low_u64_be(subject),
+                    duration,
+                    VotingRules::open(),
+                    None,
+                )
+                .unwrap();
+            }
+        };
+        // the blocks 21 to 30 of the schedule are full
+        fill(2..102, 20);
+        let weight = AssemblyPallet::on_initialize(50);
+        assert_eq!(
+            weight,
+            db_weight.reads(2) + AssemblyPallet::voting_start_weight()
+        );
+        assert_eq!(AssemblyPallet::election_calendar(), None);
+
+        // the assembly voting is finalized in the block 31
+        System::set_block_number(2);
+        AssemblyPallet::on_initialize(51);
+        assert!(VotingPallet::active_votings(AssemblyVotingHash::get()).is_some());
+        let calendar = AssemblyPallet::election_calendar().unwrap();
+        assert_eq!(calendar.term_start, 51);
+
+        fill(102..111, 29);
+        AssemblyPallet::on_finalize(calendar.prime_min_voting_start);
+        assert!(VotingPallet::active_votings(PrimeMinVotingHash::get()).is_none());
+        let prime_min_voting_start = calendar.prime_min_voting_start + 1;
+        assert_eq!(
+            AssemblyPallet::election_calendar()
+                .unwrap()
+                .prime_min_voting_start,
+            prime_min_voting_start
+        );
+        System::set_block_number(3);
+        let weight = AssemblyPallet::on_initialize(prime_min_voting_start);
+        assert_eq!(
+            weight,
+            db_weight.reads(2) + AssemblyPallet::voting_start_weight()
+        );
+        AssemblyPallet::on_finalize(prime_min_voting_start);
+        assert!(VotingPallet::active_votings(PrimeMinVotingHash::get()).is_some());
+
+        let schedule_is_full = DispatchError::from(pallet_voting::Error::<Test>::ScheduleIsFull);
+        let events: Vec<_> = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                mock::Event::pallet_assembly(event) => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                crate::Event::AssemblyVotingNotStarted(schedule_is_full.clone()),
+                crate::Event::AssemblyVotingStarted(51),
+                crate::Event::PrimeMinVotingNotStarted(schedule_is_full),
+                crate::Event::PrimeMinVotingStarted,
+            ]
+        );
+    });
+}
+
+#[test]
+fn law_lifetime_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        IdentityPallet::match_account_to_id(1, [1; 32]);
+        IdentityPallet::push_identity([1; 32], IdentityType::Assembly).unwrap();
+        AssemblyPallet::on_initialize(50);
+        type Hashing = <Test as frame_system::Config>::Hashing;
+
+        // the term ends in the block 100, the law voting takes 10 blocks
+        System::set_block_number(91);
+        assert_err!(
+            AssemblyPallet::propose_law(
+                Origin::signed(1),
+                Hashing::hash(&[1; 32]),
+                LawType::Decision
+            ),
+            <Error<Test>>::LifeTimeIsLessThanLaws
+        );
+        System::set_block_number(90);
+        assert_ok!(AssemblyPallet::propose_law(
+            Origin::signed(1),
+            Hashing::hash(&[1; 32]),
+            LawType::Decision
+        ));
+    });
+}
+
+#[test]
+fn referendum_override_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        IdentityPallet::match_account_to_id(1, [1; 32]);
+        IdentityPallet::push_identity([1; 32], IdentityType::Assembly).unwrap();
+        AssemblyPallet::on_initialize(50);
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let law_hash = Hashing::hash(&[1; 32]);
+        assert_err!(
+            AssemblyPallet::override_law(Origin::root(), law_hash, LawState::Approved),
+            <Error<Test>>::LawNotFound
+        );
+        assert_ok!(AssemblyPallet::propose_law(
+            Origin::signed(1),
+            law_hash,
+            LawType::Decision
+        ));
+        assert_err!(
+            AssemblyPallet::override_law(Origin::root(), law_hash, LawState::Approved),
+            <Error<Test>>::LawVotingInProgress
+        );
+
+        // nobody voted, so the assembly declined the law
+        VotingPallet::on_finalize(11);
+        assert_eq!(
+            AssemblyPallet::laws(law_hash).unwrap().state,
+            LawState::Declined
+        );
+        assert_err!(
+            AssemblyPallet::override_law(Origin::signed(1), law_hash, LawState::Approved),
+            DispatchError::BadOrigin
+        );
+        assert_err!(
+            AssemblyPallet::override_law(Origin::root(), law_hash, LawState::InProgress),
+            <Error<Test>>::InvalidLawState
+        );
+        assert_ok!(AssemblyPallet::override_law(
+            Origin::root(),
+            law_hash,
+            LawState::Approved
+        ));
+        assert_eq!(
+            AssemblyPallet::laws(law_hash).unwrap().state,
+            LawState::Approved
+        );
+
+        assert_err!(
+            AssemblyPallet::set_voting_power(Origin::signed(1), VotingPower::OnePersonOneVote),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(AssemblyPallet::set_voting_power(
+            Origin::root(),
+            VotingPower::OnePersonOneVote
+        ));
+        assert_eq!(
+            AssemblyPallet::voting_power_setting(),
+            Some(VotingPower::OnePersonOneVote)
+        );
+    });
+}
+
+#[test]
+fn assembly_api_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        for id in 1..4 {
+            IdentityPallet::match_account_to_id(id as u64, [id; 32]);
+            IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+            AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+        }
+        Staking::liberland_bond(Origin::signed(6), 6, 2, RewardDestination::Controller).unwrap();
+        IdentityPallet::match_account_to_id(6, [6; 32]);
+        IdentityPallet::push_identity([6; 32], IdentityType::Citizen).unwrap();
+        assert_eq!(AssemblyPallet::next_term_start(), 50);
+
+        AssemblyPallet::on_initialize(50);
+        assert_eq!(AssemblyPallet::next_term_start(), 100);
+        let ballot = pallet_voting::AltVote::new(VecDeque::from(vec![
+            [2_u8; 32].to_vec(),
+            [1_u8; 32].to_vec(),
+            [3_u8; 32].to_vec(),
+        ]));
+        assert_ok!(AssemblyPallet::vote(Origin::signed(6), ballot));
+        VotingPallet::on_finalize(21);
+
+        let assemblies: BTreeMap<_, _> = AssemblyPallet::get_current_assemblies()
+            .into_iter()
+            .collect();
+        assert_eq!(assemblies[&[2_u8; 32].to_vec()], 2);
+
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let law_hash = Hashing::hash(&[1; 32]);
+        assert_ok!(AssemblyPallet::propose_law(
+            Origin::signed(2),
+            law_hash,
+            LawType::Decision
+        ));
+        assert_eq!(
+            AssemblyPallet::get_laws(LawState::InProgress)
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&law_hash]
+        );
+        assert!(AssemblyPallet::get_laws(LawState::Approved).is_empty());
+        assert_eq!(AssemblyPallet::current_prime_min(), None);
+    });
+}
+
+#[test]
+fn assembly_api_serde_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        for account in [1, 2, 6] {
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        Staking::liberland_bond(Origin::signed(6), 6, 2, RewardDestination::Controller).unwrap();
+        assert_ok!(AssemblyPallet::add_candidate(
+            Origin::signed(1),
+            vec![1],
+            H256::repeat_byte(1)
+        ));
+        AssemblyPallet::add_candidate_internal([2; 32]).unwrap();
+
+        // the candidates are the (candidate, profile) pairs
+        let candidates = AssemblyPallet::get_candidates();
+        let json = serde_json::to_value(&candidates).unwrap();
+        assert_eq!(json[0][0], serde_json::to_value([1_u8; 32].to_vec()).unwrap());
+        assert_eq!(json[0][1]["depositor"], 1);
+        assert!(json[1][1].is_null());
+        assert_eq!(
+            serde_json::from_value::<Vec<(Candidate, Option<CandidateProfile<u64, Balance, H256>>)>>(
+                json
+            )
+            .unwrap(),
+            candidates
+        );
+
+        AssemblyPallet::on_initialize(50);
+        let ballot = pallet_voting::AltVote::new(VecDeque::from(vec![[1_u8; 32].to_vec()]));
+        assert_ok!(AssemblyPallet::vote(Origin::signed(6), ballot));
+        VotingPallet::on_finalize(21);
+
+        // a map with the candidate keys is not valid JSON
+        assert!(serde_json::to_string(&AssemblyPallet::ministers_list()).is_err());
+        let assemblies = AssemblyPallet::get_current_assemblies();
+        let json = serde_json::to_value(&assemblies).unwrap();
+        assert_eq!(json[0][1], 2);
+        assert_eq!(
+            serde_json::from_value::<Vec<(Candidate, u64)>>(json).unwrap(),
+            assemblies
+        );
+    });
+}
+
+#[test]
+fn assembly_events_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        for account in [1, 2, 6] {
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        Staking::liberland_bond(Origin::signed(6), 6, 2, RewardDestination::Controller).unwrap();
+        let candidate = [1_u8; 32].to_vec();
+        for account in [1, 2] {
+            assert_ok!(AssemblyPallet::add_candidate(
+                Origin::signed(account),
+                vec![],
+                Default::default()
+            ));
+        }
+        assert_ok!(AssemblyPallet::withdraw_candidacy(Origin::signed(2)));
+
+        AssemblyPallet::on_initialize(50);
+        let ballot = pallet_voting::AltVote::new(VecDeque::from(vec![candidate.clone()]));
+        assert_ok!(AssemblyPallet::vote(Origin::signed(6), ballot));
+        VotingPallet::on_finalize(21);
+        let elected = AssemblyPallet::ministers_list();
+
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let law_hash = Hashing::hash(&[1; 32]);
+        assert_ok!(AssemblyPallet::propose_law(
+            Origin::signed(1),
+            law_hash,
+            LawType::Decision
+        ));
+        assert_ok!(AssemblyPallet::vote_to_law(
+            Origin::signed(1),
+            law_hash,
+            Decision::Accept
+        ));
+        // the only member voted, so the law voting is closed in this block
+        VotingPallet::on_finalize(1);
+        assert_ok!(AssemblyPallet::change_support(
+            Origin::signed(6),
+            candidate.clone(),
+            -1
+        ));
+
+        let events: Vec<_> = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                mock::Event::pallet_assembly(event) => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                crate::Event::CandidateAdded(candidate.clone()),
+                crate::Event::CandidateAdded([2_u8; 32].to_vec()),
+                crate::Event::CandidacyWithdrawn([2_u8; 32].to_vec()),
+                crate::Event::AssemblyVotingStarted(50),
+                crate::Event::AssemblyVoteCast(6),
+                crate::Event::AssemblyElected(elected),
+                crate::Event::LawProposed(law_hash, LawType::Decision),
+                crate::Event::LawVoteCast(law_hash, 1, Decision::Accept),
+                crate::Event::LawDecided(law_hash, LawState::Approved),
+                crate::Event::SupportChanged(6, candidate, 1, 1),
+            ]
+        );
+    });
+}
+
+#[test]
+fn support_ledger_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        for id in 1..5 {
+            IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+            AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+        }
+        for (account, stake) in [(6, 1), (8, 3), (9, 1)] {
+            Staking::liberland_bond(
+                Origin::signed(account),
+                account,
+                stake,
+                RewardDestination::Controller,
+            )
+            .unwrap();
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        AssemblyPallet::on_initialize(50);
+        for (account, candidate) in [(6, 2_u8), (8, 1), (9, 3)] {
+            let ballot =
+                pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]));
+            assert_ok!(AssemblyPallet::vote(Origin::signed(account), ballot));
+        }
+        VotingPallet::on_finalize(21);
+        let power = |member: u8| AssemblyPallet::ministers_list()[&[member; 32].to_vec()];
+        assert_eq!(AssemblyPallet::supports([8; 32], [1_u8; 32].to_vec()), 3);
+        assert_eq!((power(1), power(2), power(3)), (3, 1, 1));
+
+        // the whole stake of 8 already supports 1
+        assert_ok!(AssemblyPallet::change_support(
+            Origin::signed(8),
+            [2_u8; 32].to_vec(),
+            1
+        ));
+        assert_eq!(power(2), 1);
+        assert_ok!(AssemblyPallet::change_support(
+            Origin::signed(8),
+            [1_u8; 32].to_vec(),
+            -1
+        ));
+        // only the freed stake moves to 2
+        assert_ok!(AssemblyPallet::change_support(
+            Origin::signed(8),
+            [2_u8; 32].to_vec(),
+            2
+        ));
+        assert_eq!((power(1), power(2)), (2, 2));
+        // 6 can not withdraw the support of 8
+        assert_ok!(AssemblyPallet::change_support(
+            Origin::signed(6),
+            [1_u8; 32].to_vec(),
+            -1
+        ));
+        assert_eq!(power(1), 2);
+
+        // 8 unbonds 1 of the liber stake, the largest support is reduced
+        assert_ok!(Staking::liberland_unbond_on(Origin::signed(8)));
+        Staking::on_initialize(System::block_number() + 1);
+        pallet_staking::CurrentEra::put(Staking::ledger(8).unwrap().unlocking[0].era);
+        assert_ok!(Staking::withdraw_unbonded(Origin::signed(8), 0));
+        assert_eq!(Staking::ledger(8).unwrap().liber_amount, 2);
+        assert_eq!(AssemblyPallet::supports([8; 32], [1_u8; 32].to_vec()), 1);
+        assert_eq!(AssemblyPallet::supports([8; 32], [2_u8; 32].to_vec()), 1);
+        assert_eq!((power(1), power(2), power(3)), (1, 2, 1));
+    });
+}
+
+#[test]
+fn seeded_support_cap_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        for id in 1..3 {
+            IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+            AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+        }
+        for (account, stake) in [(8, 3), (9, 1)] {
+            Staking::liberland_bond(
+                Origin::signed(account),
+                account,
+                stake,
+                RewardDestination::Controller,
+            )
+            .unwrap();
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        AssemblyPallet::on_initialize(50);
+        for (account, candidate) in [(8, 1_u8), (9, 2)] {
+            let ballot =
+                pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]));
+            assert_ok!(AssemblyPallet::vote(Origin::signed(account), ballot));
+        }
+
+        // 8 unbonds 1 of the liber stake after voting with the snapshot of 3
+        assert_ok!(Staking::liberland_unbond_on(Origin::signed(8)));
+        Staking::on_initialize(System::block_number() + 1);
+        pallet_staking::CurrentEra::put(Staking::ledger(8).unwrap().unlocking[0].era);
+        assert_ok!(Staking::withdraw_unbonded(Origin::signed(8), 0));
+        VotingPallet::on_finalize(21);
+
+        // the seeded support is capped by the current power of 8
+        let power = |member: u8| AssemblyPallet::ministers_list()[&[member; 32].to_vec()];
+        assert_eq!(AssemblyPallet::supports([8; 32], [1_u8; 32].to_vec()), 2);
+        assert_eq!(AssemblyPallet::supports([9; 32], [2_u8; 32].to_vec()), 1);
+        assert_eq!((power(1), power(2)), (2, 1));
+    });
+}
+
+#[test]
+fn surplus_support_test() {
+    AssemblyCountingMethod::set(CountingMethod::SingleTransferableVote);
+    ExtBuilder::default().build_and_execute(|| {
+        for id in 1..4 {
+            IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+            AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+        }
+        for (account, stake) in [(6, 1), (7, 1), (8, 3), (9, 1)] {
+            Staking::liberland_bond(
+                Origin::signed(account),
+                account,
+                stake,
+                RewardDestination::Controller,
+            )
+            .unwrap();
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        AssemblyPallet::on_initialize(50);
+        // the quota is 2, the surplus of 1 moves from 1 to 2 in the count
+        for (account, preferences) in [(8, vec![1_u8, 2]), (9, vec![2]), (6, vec![3]), (7, vec![3])]
+        {
+            let ballot = pallet_voting::AltVote::new(
+                preferences
+                    .into_iter()
+                    .map(|candidate| [candidate; 32].to_vec())
+                    .collect(),
+            );
+            assert_ok!(AssemblyPallet::vote(Origin::signed(account), ballot));
+        }
+        VotingPallet::on_finalize(21);
+
+        // the power of every member is the sum of the supports
+        let supports = |citizens: &[u8], member: u8| -> u64 {
+            citizens
+                .iter()
+                .map(|citizen| AssemblyPallet::supports([*citizen; 32], [member; 32].to_vec()))
+                .sum()
+        };
+        let citizens = [6, 7, 8, 9];
+        let mut powers = BTreeMap::new();
+        for member in 1..4 {
+            powers.insert([member; 32].to_vec(), supports(&citizens, member));
+        }
+        assert_eq!(AssemblyPallet::ministers_list(), powers);
+        assert_eq!(
+            (
+                supports(&citizens, 1),
+                supports(&citizens, 2),
+                supports(&citizens, 3)
+            ),
+            (3, 1, 2)
+        );
+    });
+    AssemblyCountingMethod::set(CountingMethod::InstantRunoff);
+}
+
+#[test]
+fn old_ballots_migration_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        use frame_support::storage::migration::{get_storage_value, put_storage_value};
+        use frame_support::StorageHasher;
+        let module = b"AssemblyPallet";
+        for account in [1_u64, 2] {
+            let key = frame_support::Blake2_128Concat::hash(&account.encode());
+            put_storage_value(module, b"Ballots", &key, 5_u64);
+        }
+        let key = frame_support::Blake2_128Concat::hash(&1_u64.encode());
+        assert_eq!(get_storage_value::<u64>(module, b"Ballots", &key), Some(5));
+
+        let db_weight = frame_support::weights::constants::RocksDbWeight::get();
+        AssemblyPallet::on_runtime_upgrade();
+        assert_eq!(get_storage_value::<u64>(module, b"Ballots", &key), None);
+        // the calendar is not seeded before the first term either
+        assert_eq!(AssemblyPallet::on_runtime_upgrade(), db_weight.reads(3));
+    });
+}
+
+#[test]
+fn stake_snapshot_test() {
+    ExtBuilder::default().build_and_execute(|| {
+        IdentityPallet::push_identity([1; 32], IdentityType::Citizen).unwrap();
+        AssemblyPallet::add_candidate_internal([1; 32]).unwrap();
+        for account in [6, 9] {
+            IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+            IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+        }
+        Staking::liberland_bond(Origin::signed(6), 6, 2, RewardDestination::Controller).unwrap();
+        // the snapshot is taken when the voting opens, the three identities are read, then the
+        // accounts of the citizens and the bonds and the ledgers of the two accounts
+        let db_weight = frame_support::weights::constants::RocksDbWeight::get();
+        assert_eq!(
+            AssemblyPallet::on_initialize(50),
+            db_weight.reads(2)
+                + AssemblyPallet::voting_start_weight()
+                + db_weight.reads_writes(10, 1)
+        );
+        assert_eq!(AssemblyPallet::stake_snapshot([6; 32]), 2);
+        assert_eq!(AssemblyPallet::stake_snapshot([1; 32]), 0);
+
+        // the stake bonded after the voting opened is not counted in it
+        Staking::liberland_bond(Origin::signed(9), 9, 4, RewardDestination::Controller).unwrap();
+        IdentityPallet::match_account_to_id(7, [6; 32]);
+        Staking::liberland_bond(Origin::signed(7), 7, 5, RewardDestination::Controller).unwrap();
+        let ballot = pallet_voting::AltVote::new(VecDeque::from(vec![[1_u8; 32].to_vec()]));
+        assert_ok!(AssemblyPallet::vote(Origin::signed(6), ballot.clone()));
+        assert_ok!(AssemblyPallet::vote(Origin::signed(9), ballot.clone()));
+        assert_err!(
+            AssemblyPallet::vote(Origin::signed(7), ballot),
+            <Error<Test>>::AlreadyVoted
+        );
+        VotingPallet::on_finalize(21);
+
+        let mut winners = BTreeMap::new();
+        winners.insert([1_u8; 32].to_vec(), 2);
+        assert_eq!(AssemblyPallet::ministers_list(), winners);
+
+        // the support changes do not take snapshots
+        assert_ok!(AssemblyPallet::change_support(
+            Origin::signed(7),
+            [1_u8; 32].to_vec(),
+            1
+        ));
+        assert_eq!(AssemblyPallet::stake_snapshot([6; 32]), 2);
+
+        // the next voting replaces the snapshot with the stake of all linked accounts
+        let next_term = AssemblyPallet::next_term_start();
+        AssemblyPallet::add_candidate_internal([1; 32]).unwrap();
+        System::set_block_number(next_term);
+        AssemblyPallet::on_initialize(next_term);
+        assert_eq!(AssemblyPallet::stake_snapshot([6; 32]), 7);
+        assert_eq!(AssemblyPallet::stake_snapshot([9; 32]), 4);
+    });
+}
+
+#[test]
+fn voting_power_test() {
+    // a citizen with a large stake votes for 1, three citizens with small stakes vote for 2
+    let elect = |voting_power: VotingPower| {
+        AssemblyVotingPower::set(voting_power);
+        let mut powers = (0, 0);
+        ExtBuilder::default().build_and_execute(|| {
+            for id in 1..3 {
+                IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+                AssemblyPallet::add_candidate_internal([id; 32]).unwrap();
+            }
+            for (account, stake) in [(3, 100), (6, 8), (7, 4), (8, 1)] {
+                Staking::liberland_bond(
+                    Origin::signed(account),
+                    account,
+                    stake,
+                    RewardDestination::Controller,
+                )
+                .unwrap();
+                IdentityPallet::match_account_to_id(account, [account as u8; 32]);
+                IdentityPallet::push_identity([account as u8; 32], IdentityType::Citizen).unwrap();
+            }
+            AssemblyPallet::on_initialize(50);
+            for (account, candidate) in [(3, 1_u8), (6, 2), (7, 2), (8, 2)] {
+                let ballot =
+                    pallet_voting::AltVote::new(VecDeque::from(vec![[candidate; 32].to_vec()]));
+                assert_ok!(AssemblyPallet::vote(Origin::signed(account), ballot));
+            }
+            VotingPallet::on_finalize(21);
+            let members = AssemblyPallet::ministers_list();
+            powers = (members[&[1_u8; 32].to_vec()], members[&[2_u8; 32].to_vec()]);
+
+            // the support is capped by the power of the citizen too
+            assert_ok!(AssemblyPallet::change_support(
+                Origin::signed(3),
+                [2_u8; 32].to_vec(),
+                1
+            ));
+            assert_eq!(AssemblyPallet::ministers_list(), members);
+        });
+        powers
+    };
+
+    assert_eq!(elect(VotingPower::Linear), (100, 13));
+    assert_eq!(elect(VotingPower::SquareRoot), (10, 5));
+    assert_eq!(elect(VotingPower::CappedLinear(5)), (5, 10));
+    assert_eq!(elect(VotingPower::OnePersonOneVote), (1, 3));
+}
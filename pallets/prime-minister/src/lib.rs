@@ -1,6 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 pub use pallet::*;
-use pallet_voting::{AltVoutingSettings, Candidate};
+use pallet_voting::{BallotsView, CountResult, VotingSettings};
 #[cfg(test)]
 mod mock;
 
@@ -34,13 +34,12 @@ pub mod pallet {
     #[pallet::call]
     impl<T: Config> Pallet<T> {}
 
-    impl<T: Config> pallet_voting::finalize_voiting_trait::FinilizeAltVotingDispatchTrait<T>
-        for Pallet<T>
-    {
+    impl<T: Config> pallet_voting::FinalizeVotingDispatchTrait<T> for Pallet<T> {
         fn finalize_voting(
             _subject: T::Hash,
-            _voting_setting: AltVoutingSettings<T::BlockNumber>,
-            _winner: Candidate,
+            _voting_settings: VotingSettings<T::BlockNumber>,
+            _result: CountResult,
+            _ballots: &BallotsView<T>,
         ) {
         }
     }
@@ -19,7 +19,7 @@ frame_support::construct_runtime!(
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
         IdentityPallet: pallet_identity::{Pallet, Call, Storage},
-        VotingPallet: pallet_voting::{Pallet, Call, Storage},
+        VotingPallet: pallet_voting::{Pallet, Call, Storage, Event<T>},
         PrimeMinPallet: pallet_prime_minister::{Pallet, Call, Storage},
     }
 );
@@ -62,12 +62,22 @@ impl pallet_prime_minister::Config for Test {
 
 impl pallet_identity::Config for Test {}
 
-impl pallet_voting::Config for Test {
-    type FinalizeVotingDispatch = ();
-
-    type FinalizeAltVotingDispatch = PrimeMinPallet;
+parameter_types! {
+    pub const MaxPreferences: u32 = 16;
+    pub const MaxCandidateLength: u32 = 32;
+    pub const MaxFinalizationsPerBlock: u32 = 10;
+    pub const MaxBallotRemovalsPerBlock: u32 = 100;
+    pub const MaxDelegators: u32 = 8;
+}
 
-    type FinalizeAltVotingListDispatch = ();
+impl pallet_voting::Config for Test {
+    type Event = Event;
+    type FinalizeVotingDispatch = PrimeMinPallet;
+    type MaxPreferences = MaxPreferences;
+    type MaxCandidateLength = MaxCandidateLength;
+    type MaxFinalizationsPerBlock = MaxFinalizationsPerBlock;
+    type MaxBallotRemovalsPerBlock = MaxBallotRemovalsPerBlock;
+    type MaxDelegators = MaxDelegators;
 }
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
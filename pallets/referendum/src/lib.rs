@@ -3,17 +3,26 @@
 #![allow(clippy::unnecessary_mut_passed)]
 
 use frame_support::codec::{Decode, Encode};
+use frame_support::storage::bounded_vec::BoundedVec;
+use frame_support::traits::{Contains, Currency, EnsureOrigin, ReservableCurrency};
 pub use pallet::*;
 use pallet_identity::{IdentityTrait, IdentityType, PassportId};
-use pallet_voting::{VotingSettings, VotingTrait};
-use sp_runtime::traits::Hash;
+use pallet_voting::{
+    AltVote, BallotsView, CountResult, DelegationTopic, VotingRules, VotingSettings, VotingTrait,
+};
+use sp_runtime::{traits::Hash, PerThing, Percent};
 use sp_std::{
     cmp::{Ord, PartialOrd},
     collections::btree_map::BTreeMap,
     collections::btree_set::BTreeSet,
+    convert::TryFrom,
+    str,
     vec::Vec,
 };
 
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 #[cfg(test)]
 mod mock;
 
@@ -24,7 +33,12 @@ mod tests;
 pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
+    use frame_support::weights::{extract_actual_weight, GetDispatchInfo, PostDispatchInfo};
     use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{Dispatchable, One, Saturating};
+
+    // the weight of hashing and decoding a byte of a noted call
+    const PREIMAGE_BYTE_WEIGHT: Weight = 3_000;
 
     #[pallet::config]
     pub trait Config:
@@ -36,17 +50,68 @@ pub mod pallet {
         #[pallet::constant]
         type ReferendumDuration: Get<Self::BlockNumber>;
 
-        // 50%
-        const REFERENDUM_ACCEPTANCE_PERCENTAGE: f64 = 0.5;
+        // the reveal window of the petitions and referendums, `None` keeps the ballots open
+        #[pallet::constant]
+        type RevealDuration: Get<Option<Self::BlockNumber>>;
+
+        // the quorum and the approval threshold of the referendums by the category
+        #[pallet::constant]
+        type ConstitutionalRules: Get<ReferendumRules>;
+
+        #[pallet::constant]
+        type FiscalRules: Get<ReferendumRules>;
+
+        #[pallet::constant]
+        type OtherRules: Get<ReferendumRules>;
+
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        // reserved for a petition, returned when the petition becomes a referendum and
+        // slashed otherwise
+        #[pallet::constant]
+        type PetitionDeposit: Get<BalanceOf<Self>>;
+
+        // reserved for every byte of a noted call, returned when the call is removed
+        #[pallet::constant]
+        type PreimageByteDeposit: Get<BalanceOf<Self>>;
+
+        // the least number of blocks between two petitions of a citizen
+        #[pallet::constant]
+        type PetitionCooldown: Get<Self::BlockNumber>;
+
+        // the longest title and summary of a suggestion and the longest encoded call
+        #[pallet::constant]
+        type MaxSuggestionLength: Get<u32>;
 
         // 10%
-        const PETITION_ACCEPTANCE_PERCENTAGE: f64 = 0.1;
+        const PETITION_ACCEPTANCE_PERCENTAGE: Percent = Percent::from_parts(10);
+
+        // the blocks between the success of a binding referendum and the dispatch of its call
+        #[pallet::constant]
+        type EnactmentDelay: Get<Self::BlockNumber>;
+
+        // the most binding referendums enacted in a block, the rest are enacted in the next ones
+        #[pallet::constant]
+        type MaxEnactmentsPerBlock: Get<u32>;
+
+        // the calls of the binding referendums are dispatched with `RawOrigin::Referendum`
+        type Origin: From<RawOrigin>;
+
+        type Proposal: Parameter
+            + Dispatchable<Origin = <Self as Config>::Origin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo;
 
         type VotingTrait: pallet_voting::VotingTrait<Self>;
 
         type IdentityTrait: pallet_identity::IdentityTrait<Self>;
+
+        // the laws of the assembly a suggestion can refer to by the hash
+        type Laws: Contains<Self::Hash>;
     }
 
+    #[pallet::origin]
+    pub type Origin = RawOrigin;
+
     #[pallet::pallet]
     #[pallet::generate_store(trait Store)]
     pub struct Pallet<T>(_);
@@ -57,22 +122,132 @@ pub mod pallet {
         AccountCannotVote,
         SubjectDoesNotExist,
         AlreadyVoted,
+        InvalidCall,
+        PetitionAlreadyExists,
+        SuggestionTooLong,
+        InvalidText,
+        PetitionCooldown,
+        InsufficientDeposit,
+        LawDoesNotExist,
+        DelegateIsNotCitizen,
+        PreimageNotFound,
+        CallTooHeavy,
+        PreimageAlreadyNoted,
+        NotPreimageDepositor,
+        PreimageInUse,
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        // the calls which do not fit into the block with the calls enacted before them are
+        // enacted in the next block, the first call always fits
+        fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+            let max_weight = T::BlockWeights::get().max_block;
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            let mut dispatched: Option<Weight> = None;
+            for subject in <SomeEnactments<T>>::take(block_number) {
+                weight = weight.saturating_add(T::DbWeight::get().reads(2));
+                let call_hash = match <SomeSuccessfulReferendums<T>>::get(subject)
+                    .and_then(|referendum| referendum.call)
+                {
+                    Some(call_hash) => call_hash,
+                    None => continue,
+                };
+                let call = match Self::preimage_call(call_hash) {
+                    Some(call) => call,
+                    // a call which can not be decoded any more is dropped
+                    None => {
+                        Self::release_preimage(call_hash);
+                        weight = weight.saturating_add(Self::release_weight());
+                        continue;
+                    }
+                };
+                let call_weight = call.get_dispatch_info().weight;
+                if dispatched.map_or(false, |dispatched| {
+                    dispatched.saturating_add(call_weight) > max_weight
+                }) {
+                    Self::schedule_enactment(subject, block_number.saturating_add(One::one()));
+                    weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+                    continue;
+                }
+                dispatched = Some(
+                    dispatched
+                        .unwrap_or_default()
+                        .saturating_add(Self::enact(call)),
+                );
+                Self::release_preimage(call_hash);
+                weight = weight.saturating_add(Self::release_weight());
+            }
+            weight.saturating_add(dispatched.unwrap_or_default())
+        }
+    }
 
     #[pallet::storage]
     type SomeActivePetitions<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::Hash, Suggestion, OptionQuery>;
+        StorageMap<_, Blake2_128Concat, T::Hash, BoundedSuggestionOf<T>, OptionQuery>;
 
     #[pallet::storage]
     type SomeActiveReferendums<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::Hash, Suggestion, OptionQuery>;
+        StorageMap<_, Blake2_128Concat, T::Hash, BoundedSuggestionOf<T>, OptionQuery>;
 
     #[pallet::storage]
     type SomeSuccessfulReferendums<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::Hash, Suggestion, OptionQuery>;
+        StorageMap<_, Blake2_128Concat, T::Hash, BoundedSuggestionOf<T>, OptionQuery>;
+
+    // the choices of the citizens in the petitions and referendums
+    #[pallet::storage]
+    type SomeTallies<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, Tally, ValueQuery>;
+
+    // the successful binding referendums by the block their calls are dispatched in
+    #[pallet::storage]
+    type SomeEnactments<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<T::Hash, T::MaxEnactmentsPerBlock>,
+        ValueQuery,
+    >;
+
+    // the encoded calls of the binding referendums by the hash, the suggestions refer to them
+    #[pallet::storage]
+    #[pallet::getter(fn preimage)]
+    type SomePreimages<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::Hash,
+        BoundedVec<u8, T::MaxSuggestionLength>,
+        OptionQuery,
+    >;
+
+    // the depositors of the noted calls with their deposits and the number of the active
+    // suggestions referring to the calls, a call is removed once no suggestion refers to it
+    #[pallet::storage]
+    type SomePreimageDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::Hash, (T::AccountId, BalanceOf<T>, u32), OptionQuery>;
+
+    // the depositors of the active petitions and their deposits
+    #[pallet::storage]
+    type SomePetitionDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::Hash, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+    // the block of the last petition of a citizen
+    #[pallet::storage]
+    type SomeLastPetitions<T: Config> =
+        StorageMap<_, Blake2_128Concat, PassportId, T::BlockNumber, OptionQuery>;
+
+    // the tallies and the opening blocks of the petitions which became referendums
+    #[pallet::storage]
+    type SomeClosedPetitions<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::Hash, (Tally, T::BlockNumber), OptionQuery>;
+
+    // every closed suggestion by the order it was closed in
+    #[pallet::storage]
+    type SomeArchive<T: Config> =
+        StorageMap<_, Twox64Concat, u32, BoundedClosedSuggestionOf<T>, OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn get_closed_suggestions_count)]
+    type SomeArchiveLength<T: Config> = StorageValue<_, u32, ValueQuery>;
 
     #[pallet::type_value]
     pub fn VotedCitizensDefault() -> BTreeSet<PassportId> {
@@ -89,40 +264,250 @@ pub mod pallet {
         VotedCitizensDefault,
     >;
 
+    // the votes cast for the citizens by their delegates in the referendums,
+    // a citizen replaces the vote by voting
+    #[pallet::storage]
+    type SomeDelegatedVotes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::Hash,
+        Blake2_128Concat,
+        T::AccountId,
+        ReferendumVote,
+        OptionQuery,
+    >;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         // propose a petition
         #[pallet::weight(1)]
         pub(super) fn suggest_petition(
             origin: OriginFor<T>,
-            petition: Suggestion,
+            petition: Suggestion<T::Hash>,
         ) -> DispatchResultWithPostInfo {
             let sender = ensure_signed(origin)?;
 
             ensure!(
-                T::IdentityTrait::check_account_identity(sender, IdentityType::Citizen),
+                T::IdentityTrait::check_account_identity(sender.clone(), IdentityType::Citizen),
                 <Error<T>>::AccountCannotSuggestPetition,
             );
+            // the texts are shown as strings
+            ensure!(
+                str::from_utf8(&petition.title).is_ok()
+                    && str::from_utf8(&petition.summary).is_ok(),
+                <Error<T>>::InvalidText
+            );
+            let petition_hash = Self::get_suggestion_hash(&petition);
+            let petition: BoundedSuggestionOf<T> = petition
+                .try_map_text(|text| BoundedVec::try_from(text).ok())
+                .ok_or(<Error<T>>::SuggestionTooLong)?;
+            if let Some(law) = petition.law {
+                ensure!(T::Laws::contains(&law), <Error<T>>::LawDoesNotExist);
+            }
+
+            if let Some(call_hash) = petition.call {
+                let call =
+                    <SomePreimages<T>>::get(call_hash).ok_or(<Error<T>>::PreimageNotFound)?;
+                let call =
+                    T::Proposal::decode(&mut &call[..]).map_err(|_| <Error<T>>::InvalidCall)?;
+                // a call heavier than a block could never be enacted
+                ensure!(
+                    call.get_dispatch_info().weight <= T::BlockWeights::get().max_block,
+                    <Error<T>>::CallTooHeavy
+                );
+            }
 
-            let petition_hash = T::Hashing::hash(&petition.data[..]);
+            ensure!(
+                !<SomeActivePetitions<T>>::contains_key(petition_hash)
+                    && !<SomeActiveReferendums<T>>::contains_key(petition_hash),
+                <Error<T>>::PetitionAlreadyExists
+            );
 
-            T::VotingTrait::create_voting(petition_hash, T::PetitionDuration::get(), None)?;
+            let passport_id = pallet_identity::Pallet::<T>::passport_id(sender.clone()).unwrap();
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            if let Some(last_petition) = <SomeLastPetitions<T>>::get(passport_id) {
+                ensure!(
+                    block_number >= last_petition.saturating_add(T::PetitionCooldown::get()),
+                    <Error<T>>::PetitionCooldown
+                );
+            }
+            let deposit = T::PetitionDeposit::get();
+            ensure!(
+                T::Currency::can_reserve(&sender, deposit),
+                <Error<T>>::InsufficientDeposit
+            );
+
+            T::VotingTrait::create_voting(
+                petition_hash,
+                T::PetitionDuration::get(),
+                VotingRules::open(),
+                T::RevealDuration::get(),
+            )?;
+            T::Currency::reserve(&sender, deposit)?;
+            <SomePetitionDeposits<T>>::insert(petition_hash, (sender, deposit));
+            if let Some(call_hash) = petition.call {
+                Self::use_preimage(call_hash);
+            }
+            <SomeLastPetitions<T>>::insert(passport_id, block_number);
             <SomeActivePetitions<T>>::insert(petition_hash, petition);
 
             Ok(().into())
         }
 
-        #[pallet::weight(1)]
+        // notes the encoded call of a binding referendum, the suggestion refers to it by
+        // `preimage_hash`, the deposit for the bytes of the call is reserved until the call
+        // is removed
+        #[pallet::weight(Pallet::<T>::note_preimage_weight(encoded_call.len()))]
+        pub(super) fn note_preimage(
+            origin: OriginFor<T>,
+            encoded_call: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            ensure!(
+                T::IdentityTrait::check_account_identity(sender, IdentityType::Citizen),
+                <Error<T>>::AccountCannotSuggestPetition,
+            );
+            let encoded_call = BoundedVec::<u8, T::MaxSuggestionLength>::try_from(encoded_call)
+                .map_err(|_| <Error<T>>::SuggestionTooLong)?;
+            ensure!(
+                T::Proposal::decode(&mut &encoded_call[..]).is_ok(),
+                <Error<T>>::InvalidCall
+            );
+            let call_hash = Self::preimage_hash(&encoded_call);
+            ensure!(
+                !<SomePreimages<T>>::contains_key(call_hash),
+                <Error<T>>::PreimageAlreadyNoted
+            );
+            let deposit =
+                T::PreimageByteDeposit::get().saturating_mul((encoded_call.len() as u32).into());
+            T::Currency::reserve(&sender, deposit).map_err(|_| <Error<T>>::InsufficientDeposit)?;
+            <SomePreimageDeposits<T>>::insert(call_hash, (sender, deposit, 0));
+            <SomePreimages<T>>::insert(call_hash, encoded_call);
+            Ok(().into())
+        }
+
+        // removes the noted call no suggestion refers to and returns the deposit
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+        pub(super) fn unnote_preimage(
+            origin: OriginFor<T>,
+            call_hash: T::Hash,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let (depositor, _, users) =
+                <SomePreimageDeposits<T>>::get(call_hash).ok_or(<Error<T>>::PreimageNotFound)?;
+            ensure!(depositor == sender, <Error<T>>::NotPreimageDepositor);
+            ensure!(users == 0, <Error<T>>::PreimageInUse);
+            Self::remove_preimage(call_hash);
+            Ok(().into())
+        }
+
+        // charged for `MaxDelegators` votes cast for the delegators, the rest is refunded
+        #[pallet::weight(1 + Pallet::<T>::delegators_weight(T::MaxDelegators::get()))]
         pub(super) fn vote(
             origin: OriginFor<T>,
             subject_hash: T::Hash,
+            vote: ReferendumVote,
         ) -> DispatchResultWithPostInfo {
             let sender = ensure_signed(origin)?;
+            let passport_id = Self::ensure_can_vote(sender.clone(), subject_hash)?;
+
+            match <SomeDelegatedVotes<T>>::take(subject_hash, &sender) {
+                Some(delegated) => <SomeTallies<T>>::mutate(subject_hash, |tally| {
+                    tally.remove(delegated);
+                    tally.add(vote);
+                }),
+                None => {
+                    T::VotingTrait::vote(subject_hash, 1)?;
+                    <SomeTallies<T>>::mutate(subject_hash, |tally| tally.add(vote));
+                }
+            }
+            <SomeVotedCitizens<T>>::mutate(subject_hash, |voted| {
+                voted.insert(passport_id);
+            });
+            let delegators = if <SomeActiveReferendums<T>>::contains_key(subject_hash) {
+                Self::vote_for_delegators(subject_hash, &sender, vote)
+            } else {
+                0
+            };
+
+            Ok(Some(1 + Self::delegators_weight(delegators)).into())
+        }
 
+        // the delegate votes for the citizen in the referendums which the citizen does not
+        // vote in, the delegation is transitive, it applies to the votes the delegate casts
+        // after it
+        #[pallet::weight(1)]
+        pub(super) fn delegate_vote(
+            origin: OriginFor<T>,
+            delegate: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
             ensure!(
                 T::IdentityTrait::check_account_identity(sender.clone(), IdentityType::Citizen),
                 <Error<T>>::AccountCannotVote,
             );
+            ensure!(
+                T::IdentityTrait::check_account_identity(delegate.clone(), IdentityType::Citizen),
+                <Error<T>>::DelegateIsNotCitizen,
+            );
+            T::VotingTrait::delegate(DelegationTopic::Referendums, sender, delegate)?;
+            Ok(().into())
+        }
+
+        #[pallet::weight(1)]
+        pub(super) fn undelegate_vote(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            T::VotingTrait::undelegate(DelegationTopic::Referendums, sender)?;
+            Ok(().into())
+        }
+
+        // the secret vote, `commitment` is `pallet_voting::Pallet::commitment_of` the sender,
+        // the subject, the empty ballot and `secret_salt` of the vote and the salt
+        #[pallet::weight(1)]
+        pub(super) fn commit_vote(
+            origin: OriginFor<T>,
+            subject_hash: T::Hash,
+            commitment: T::Hash,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let passport_id = Self::ensure_can_vote(sender.clone(), subject_hash)?;
+
+            T::VotingTrait::commit_vote(subject_hash, sender, commitment, 1)?;
+            <SomeVotedCitizens<T>>::mutate(subject_hash, |voted| {
+                voted.insert(passport_id);
+            });
+
+            Ok(().into())
+        }
+
+        #[pallet::weight(1)]
+        pub(super) fn reveal_vote(
+            origin: OriginFor<T>,
+            subject_hash: T::Hash,
+            vote: ReferendumVote,
+            salt: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            T::VotingTrait::reveal_vote(
+                subject_hash,
+                sender,
+                AltVote::default(),
+                Self::secret_salt(vote, salt),
+            )?;
+            <SomeTallies<T>>::mutate(subject_hash, |tally| tally.add(vote));
+            Ok(().into())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        fn ensure_can_vote(
+            account_id: T::AccountId,
+            subject_hash: T::Hash,
+        ) -> Result<PassportId, Error<T>> {
+            ensure!(
+                T::IdentityTrait::check_account_identity(account_id.clone(), IdentityType::Citizen),
+                <Error<T>>::AccountCannotVote,
+            );
 
             ensure!(
                 <SomeActivePetitions<T>>::get(subject_hash) != None
@@ -130,81 +515,491 @@ pub mod pallet {
                 <Error<T>>::SubjectDoesNotExist,
             );
 
-            let mut voted = <SomeVotedCitizens<T>>::get(subject_hash);
-            let passport_id = pallet_identity::Pallet::<T>::passport_id(sender).unwrap();
+            let passport_id =
+                pallet_identity::Pallet::<T>::passport_id(account_id.clone()).unwrap();
+            // the vote cast by a delegate is replaced by the vote of the citizen
+            ensure!(
+                !<SomeVotedCitizens<T>>::get(subject_hash).contains(&passport_id)
+                    || <SomeDelegatedVotes<T>>::contains_key(subject_hash, &account_id),
+                <Error<T>>::AlreadyVoted
+            );
+            Ok(passport_id)
+        }
 
-            ensure!(!voted.contains(&passport_id), <Error<T>>::AlreadyVoted);
+        // the weight of the votes cast for the delegators
+        pub fn delegators_weight(delegators: u32) -> Weight {
+            T::DbWeight::get()
+                .reads_writes(6, 3)
+                .saturating_mul(delegators as Weight)
+        }
 
-            T::VotingTrait::vote(subject_hash, 1)?;
-            voted.insert(passport_id);
+        // the vote of the delegate is cast for the citizens who delegated to the delegate and
+        // did not vote themselves, the vote of a closer delegate replaces it,
+        // returns the number of the delegators, at most `MaxDelegators`
+        fn vote_for_delegators(
+            subject_hash: T::Hash,
+            delegate: &T::AccountId,
+            vote: ReferendumVote,
+        ) -> u32 {
+            let mut voted = <SomeVotedCitizens<T>>::get(subject_hash);
+            let voted_directly = |account: &T::AccountId| {
+                !<SomeDelegatedVotes<T>>::contains_key(subject_hash, account)
+                    && pallet_identity::Pallet::<T>::passport_id(account.clone())
+                        .map_or(false, |passport_id| voted.contains(&passport_id))
+            };
+            let delegators = T::VotingTrait::delegated_voters(
+                DelegationTopic::Referendums,
+                delegate,
+                voted_directly,
+            );
+            let number = delegators.len() as u32;
+            for delegator in delegators {
+                if !T::IdentityTrait::check_account_identity(
+                    delegator.clone(),
+                    IdentityType::Citizen,
+                ) {
+                    continue;
+                }
+                match <SomeDelegatedVotes<T>>::get(subject_hash, &delegator) {
+                    Some(previous) => <SomeTallies<T>>::mutate(subject_hash, |tally| {
+                        tally.remove(previous);
+                        tally.add(vote);
+                    }),
+                    None => {
+                        //this unwrap() is correct
+                        let passport_id =
+                            pallet_identity::Pallet::<T>::passport_id(delegator.clone()).unwrap();
+                        // the citizen already voted with another account
+                        if voted.contains(&passport_id)
+                            || T::VotingTrait::vote(subject_hash, 1).is_err()
+                        {
+                            continue;
+                        }
+                        voted.insert(passport_id);
+                        <SomeTallies<T>>::mutate(subject_hash, |tally| tally.add(vote));
+                    }
+                }
+                <SomeDelegatedVotes<T>>::insert(subject_hash, delegator, vote);
+            }
             <SomeVotedCitizens<T>>::insert(subject_hash, voted);
+            number
+        }
 
-            Ok(().into())
+        // the vote is committed together with the salt, the ballot of a motion is empty
+        pub fn secret_salt(vote: ReferendumVote, salt: Vec<u8>) -> Vec<u8> {
+            (vote, salt).encode()
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        pub fn get_suggestion_hash(suggestion: &Suggestion) -> T::Hash {
-            T::Hashing::hash(&suggestion.data[..])
+        // the hash the suggestions refer to the noted call by
+        pub fn preimage_hash(encoded_call: &[u8]) -> T::Hash {
+            T::Hashing::hash(encoded_call)
+        }
+
+        // the noted call is hashed and decoded once, the citizen, the preimage and the balance
+        // of the depositor are read
+        pub fn note_preimage_weight(length: usize) -> Weight {
+            T::DbWeight::get()
+                .reads_writes(4, 3)
+                .saturating_add((length as Weight).saturating_mul(PREIMAGE_BYTE_WEIGHT))
+        }
+
+        fn preimage_call(call_hash: T::Hash) -> Option<T::Proposal> {
+            <SomePreimages<T>>::get(call_hash)
+                .and_then(|call| T::Proposal::decode(&mut &call[..]).ok())
+        }
+
+        // one more active suggestion refers to the noted call
+        fn use_preimage(call_hash: T::Hash) {
+            <SomePreimageDeposits<T>>::mutate(call_hash, |deposit| {
+                if let Some((_, _, users)) = deposit {
+                    *users = users.saturating_add(1);
+                }
+            });
+        }
+
+        // a suggestion referring to the noted call is closed, the call is removed and the
+        // deposit is returned once no suggestion refers to it
+        fn release_preimage(call_hash: T::Hash) {
+            let users = <SomePreimageDeposits<T>>::mutate(call_hash, |deposit| {
+                deposit.as_mut().map(|(_, _, users)| {
+                    *users = users.saturating_sub(1);
+                    *users
+                })
+            });
+            if users == Some(0) {
+                Self::remove_preimage(call_hash);
+            }
+        }
+
+        fn release_weight() -> Weight {
+            T::DbWeight::get().reads_writes(2, 4)
+        }
+
+        fn remove_preimage(call_hash: T::Hash) {
+            <SomePreimages<T>>::remove(call_hash);
+            if let Some((depositor, deposit, _)) = <SomePreimageDeposits<T>>::take(call_hash) {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+        }
+
+        // the referendum is enacted in the first block from `block` which has room for it
+        fn schedule_enactment(subject: T::Hash, block: T::BlockNumber) {
+            let mut block = block;
+            while <SomeEnactments<T>>::try_mutate(block, |subjects| subjects.try_push(subject))
+                .is_err()
+            {
+                block = block.saturating_add(One::one());
+            }
+        }
+
+        // dispatches the call of the successful referendum and returns the weight it actually
+        // used, a failed call is not retried
+        fn enact(call: T::Proposal) -> Weight {
+            let info = call.get_dispatch_info();
+            let result = call.dispatch(RawOrigin::Referendum.into());
+            extract_actual_weight(&result, &info)
+        }
+
+        fn referendum_rules(category: SuggestionCategory) -> ReferendumRules {
+            match category {
+                SuggestionCategory::Constitutional => T::ConstitutionalRules::get(),
+                SuggestionCategory::Fiscal => T::FiscalRules::get(),
+                SuggestionCategory::Other => T::OtherRules::get(),
+            }
         }
 
-        pub fn get_active_petitions() -> BTreeMap<T::Hash, Suggestion> {
-            <SomeActivePetitions<T>>::iter().collect()
+        // the subject of the petition and the referendum on the suggestion
+        pub fn get_suggestion_hash(suggestion: &Suggestion<T::Hash>) -> T::Hash {
+            T::Hashing::hash_of(suggestion)
         }
 
-        pub fn get_active_referendums() -> BTreeMap<T::Hash, Suggestion> {
-            <SomeActiveReferendums<T>>::iter().collect()
+        // the active or the successful suggestion
+        pub fn get_suggestion(subject: T::Hash) -> Option<Suggestion<T::Hash>> {
+            <SomeActivePetitions<T>>::get(subject)
+                .or_else(|| <SomeActiveReferendums<T>>::get(subject))
+                .or_else(|| <SomeSuccessfulReferendums<T>>::get(subject))
+                .map(|suggestion| suggestion.map_text(|text| text.to_vec()))
         }
 
-        pub fn get_successfull_referendums() -> BTreeMap<T::Hash, Suggestion> {
-            <SomeSuccessfulReferendums<T>>::iter().collect()
+        pub fn get_active_petitions() -> BTreeMap<T::Hash, Suggestion<T::Hash>> {
+            <SomeActivePetitions<T>>::iter()
+                .map(|(subject, petition)| (subject, petition.map_text(|text| text.to_vec())))
+                .collect()
+        }
+
+        pub fn get_active_referendums() -> BTreeMap<T::Hash, Suggestion<T::Hash>> {
+            <SomeActiveReferendums<T>>::iter()
+                .map(|(subject, referendum)| (subject, referendum.map_text(|text| text.to_vec())))
+                .collect()
+        }
+
+        pub fn get_successfull_referendums() -> BTreeMap<T::Hash, Suggestion<T::Hash>> {
+            <SomeSuccessfulReferendums<T>>::iter()
+                .map(|(subject, referendum)| (subject, referendum.map_text(|text| text.to_vec())))
+                .collect()
+        }
+
+        pub fn get_closed_suggestions(
+            start: u32,
+            count: u32,
+        ) -> Vec<ClosedSuggestion<T::Hash, T::BlockNumber>> {
+            let end = start
+                .saturating_add(count)
+                .min(Self::get_closed_suggestions_count());
+            (start..end)
+                .filter_map(<SomeArchive<T>>::get)
+                .map(|closed| closed.map_text(|text| text.to_vec()))
+                .collect()
+        }
+
+        fn archive(closed: BoundedClosedSuggestionOf<T>) {
+            let index = Self::get_closed_suggestions_count();
+            <SomeArchive<T>>::insert(index, closed);
+            <SomeArchiveLength<T>>::put(index.saturating_add(1));
         }
     }
 
     impl<T: Config> pallet_voting::FinalizeVotingDispatchTrait<T> for Pallet<T> {
-        fn finalize_voting(_subject: T::Hash, _voting_setting: VotingSettings<T::BlockNumber>) {
-            //FIXME Нужно исправить данный фрагмет кода ошибка состоит в том что не правильно был сделан порядок создания и очищения голосования
-            // Одним из вариантов исправление вынести создание голосования в функцию initialize()
-            // if let Some(petition) = <SomeActivePetitions<T>>::get(subject) {
-            //     // more than 10%
-            //     if voting_setting.result
-            //         > ((pallet_identity::Pallet::<T>::citizens_amount() as f64)
-            //             * T::PETITION_ACCEPTANCE_PERCENTAGE) as u64
-            //     {
-            //         <SomeActiveReferendums<T>>::insert(subject, petition);
-            //         T::VotingTrait::create_voting(subject, T::ReferendumDuration::get()).unwrap();
-            //     }
-            //     <SomeVotedCitizens<T>>::remove(subject);
-            //     <SomeActivePetitions<T>>::remove(subject);
-            //     return;
-            // }
-            // if let Some(referendum) = <SomeActiveReferendums<T>>::get(subject) {
-            //     // more than 50%
-            //     if voting_setting.result
-            //         > ((pallet_identity::Pallet::<T>::citizens_amount() as f64)
-            //             * T::REFERENDUM_ACCEPTANCE_PERCENTAGE) as u64
-            //     {
-            //         <SomeSuccessfulReferendums<T>>::insert(subject, referendum);
-            //     }
-            //     <SomeVotedCitizens<T>>::remove(subject);
-            //     <SomeActiveReferendums<T>>::remove(subject);
-            // }
+        // the voting of the subject is already removed from the active votings, so the
+        // referendum is created with the subject of the petition
+        fn finalize_voting(
+            subject: T::Hash,
+            voting_setting: VotingSettings<T::BlockNumber>,
+            _result: CountResult,
+            _ballots: &BallotsView<T>,
+        ) {
+            let citizens_amount = pallet_identity::Pallet::<T>::citizens_amount();
+            let tally = <SomeTallies<T>>::take(subject);
+            let closed = voting_setting.submitted_height
+                + voting_setting.voting_duration
+                + voting_setting.reveal_duration.unwrap_or_default();
+            if let Some(petition) = <SomeActivePetitions<T>>::take(subject) {
+                // the citizens who supported the petition vote in the referendum again
+                <SomeVotedCitizens<T>>::remove(subject);
+                // more than 10%
+                let supported =
+                    tally.ayes > T::PETITION_ACCEPTANCE_PERCENTAGE.mul_floor(citizens_amount);
+                let escalated = supported
+                    && T::VotingTrait::create_voting(
+                        subject,
+                        T::ReferendumDuration::get(),
+                        VotingRules::open(),
+                        T::RevealDuration::get(),
+                    )
+                    .is_ok();
+                // the deposit is returned only once the referendum is created
+                if let Some((depositor, deposit)) = <SomePetitionDeposits<T>>::take(subject) {
+                    if escalated {
+                        T::Currency::unreserve(&depositor, deposit);
+                    } else {
+                        // the slashed deposit is burned
+                        let _ = T::Currency::slash_reserved(&depositor, deposit);
+                    }
+                }
+                if escalated {
+                    <SomeActiveReferendums<T>>::insert(subject, petition);
+                    <SomeClosedPetitions<T>>::insert(
+                        subject,
+                        (tally, voting_setting.submitted_height),
+                    );
+                } else {
+                    if let Some(call_hash) = petition.call {
+                        Self::release_preimage(call_hash);
+                    }
+                    Self::archive(ClosedSuggestion {
+                        subject,
+                        suggestion: petition,
+                        outcome: SuggestionOutcome::FailedPetition,
+                        petition_tally: tally,
+                        referendum_tally: None,
+                        opened: voting_setting.submitted_height,
+                        closed,
+                    });
+                }
+                return;
+            }
+            if let Some(referendum) = <SomeActiveReferendums<T>>::take(subject) {
+                <SomeVotedCitizens<T>>::remove(subject);
+                <SomeDelegatedVotes<T>>::remove_prefix(subject);
+                let rules = Self::referendum_rules(referendum.category);
+                // the revealed secret votes are the participants, the unrevealed are not
+                let participants = u64::from(voting_setting.voted);
+                let passed = participants >= rules.quorum.mul_ceil(citizens_amount)
+                    && tally.ayes > rules.approval.mul_floor(tally.ayes + tally.nays);
+                if passed {
+                    if referendum.call.is_some() {
+                        let block_number = <frame_system::Pallet<T>>::block_number();
+                        let enactment = block_number
+                            .saturating_add(T::EnactmentDelay::get())
+                            .max(block_number + One::one());
+                        Self::schedule_enactment(subject, enactment);
+                    }
+                    <SomeSuccessfulReferendums<T>>::insert(subject, referendum.clone());
+                } else if let Some(call_hash) = referendum.call {
+                    Self::release_preimage(call_hash);
+                }
+                let (petition_tally, opened) = <SomeClosedPetitions<T>>::take(subject)
+                    .unwrap_or((Tally::default(), voting_setting.submitted_height));
+                Self::archive(ClosedSuggestion {
+                    subject,
+                    suggestion: referendum,
+                    outcome: if passed {
+                        SuggestionOutcome::Passed
+                    } else {
+                        SuggestionOutcome::FailedReferendum
+                    },
+                    petition_tally,
+                    referendum_tally: Some(tally),
+                    opened,
+                    closed,
+                });
+            }
         }
     }
 }
 
 sp_api::decl_runtime_apis! {
     pub trait ReferendumPalletApi<T: Config> {
-        fn get_active_petitions() -> BTreeMap<T::Hash, Suggestion>;
+        fn get_active_petitions() -> BTreeMap<T::Hash, Suggestion<T::Hash>>;
+
+        fn get_active_referendums() -> BTreeMap<T::Hash, Suggestion<T::Hash>>;
+
+        fn get_successfull_referendums() -> BTreeMap<T::Hash, Suggestion<T::Hash>>;
+
+        fn get_suggestion(subject: T::Hash) -> Option<Suggestion<T::Hash>>;
 
-        fn get_active_referendums() -> BTreeMap<T::Hash, Suggestion>;
+        fn get_closed_suggestions_count() -> u32;
 
-        fn get_successfull_referendums() -> BTreeMap<T::Hash, Suggestion>;
+        // at most `count` of the closed suggestions from the index `start`, the oldest first
+        fn get_closed_suggestions(
+            start: u32,
+            count: u32,
+        ) -> Vec<ClosedSuggestion<T::Hash, T::BlockNumber>>;
     }
 }
 
+// the texts are bounded by `MaxSuggestionLength` in the storage
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Suggestion {
-    pub data: Vec<u8>,
+pub struct Suggestion<Hash, Text = Vec<u8>> {
+    pub title: Text,
+    pub summary: Text,
+    // the hash of the full text, which is kept off the chain
+    pub content_hash: Hash,
+    pub category: SuggestionCategory,
+    // the law of the assembly the suggestion changes
+    pub law: Option<Hash>,
+    // the preimage hash of the call of a binding referendum, it is dispatched when the
+    // referendum succeeds
+    pub call: Option<Hash>,
+}
+
+pub type BoundedSuggestionOf<T> = Suggestion<
+    <T as frame_system::Config>::Hash,
+    BoundedVec<u8, <T as Config>::MaxSuggestionLength>,
+>;
+
+impl<Hash, Text> Suggestion<Hash, Text> {
+    // the suggestion with the title and the summary converted, e.g. to the bounded texts
+    // of the storage or to the strings of the RPC, `None` if a text can not be converted
+    pub fn try_map_text<U>(self, f: impl Fn(Text) -> Option<U>) -> Option<Suggestion<Hash, U>> {
+        Some(Suggestion {
+            title: f(self.title)?,
+            summary: f(self.summary)?,
+            content_hash: self.content_hash,
+            category: self.category,
+            law: self.law,
+            call: self.call,
+        })
+    }
+
+    pub fn map_text<U>(self, f: impl Fn(Text) -> U) -> Suggestion<Hash, U> {
+        Suggestion {
+            title: f(self.title),
+            summary: f(self.summary),
+            content_hash: self.content_hash,
+            category: self.category,
+            law: self.law,
+            call: self.call,
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum SuggestionOutcome {
+    FailedPetition,
+    FailedReferendum,
+    Passed,
+}
+
+// a suggestion as it is archived when its petition or referendum is closed
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug)]
+pub struct ClosedSuggestion<Hash, BlockNumber, Text = Vec<u8>> {
+    pub subject: Hash,
+    pub suggestion: Suggestion<Hash, Text>,
+    pub outcome: SuggestionOutcome,
+    pub petition_tally: Tally,
+    // the final counts of the referendum, if the petition became one
+    pub referendum_tally: Option<Tally>,
+    // the block the petition was suggested in and the last block of its last voting
+    pub opened: BlockNumber,
+    pub closed: BlockNumber,
+}
+
+pub type BoundedClosedSuggestionOf<T> = ClosedSuggestion<
+    <T as frame_system::Config>::Hash,
+    <T as frame_system::Config>::BlockNumber,
+    BoundedVec<u8, <T as Config>::MaxSuggestionLength>,
+>;
+
+impl<Hash, BlockNumber, Text> ClosedSuggestion<Hash, BlockNumber, Text> {
+    pub fn map_text<U>(self, f: impl Fn(Text) -> U) -> ClosedSuggestion<Hash, BlockNumber, U> {
+        ClosedSuggestion {
+            subject: self.subject,
+            suggestion: self.suggestion.map_text(f),
+            outcome: self.outcome,
+            petition_tally: self.petition_tally,
+            referendum_tally: self.referendum_tally,
+            opened: self.opened,
+            closed: self.closed,
+        }
+    }
+}
+
+// the category decides the rules of the referendum on the suggestion
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum SuggestionCategory {
+    Constitutional,
+    Fiscal,
+    Other,
+}
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum ReferendumVote {
+    Aye,
+    Nay,
+    Abstain,
+}
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug, Default)]
+pub struct Tally {
+    pub ayes: u64,
+    pub nays: u64,
+    pub abstentions: u64,
+}
+
+impl Tally {
+    pub fn add(&mut self, vote: ReferendumVote) {
+        let count = match vote {
+            ReferendumVote::Aye => &mut self.ayes,
+            ReferendumVote::Nay => &mut self.nays,
+            ReferendumVote::Abstain => &mut self.abstentions,
+        };
+        *count = count.saturating_add(1);
+    }
+
+    pub fn remove(&mut self, vote: ReferendumVote) {
+        let count = match vote {
+            ReferendumVote::Aye => &mut self.ayes,
+            ReferendumVote::Nay => &mut self.nays,
+            ReferendumVote::Abstain => &mut self.abstentions,
+        };
+        *count = count.saturating_sub(1);
+    }
+}
+
+// a referendum passes when at least `quorum` of the citizens voted and more than `approval`
+// of the ayes and nays are ayes, the abstentions count only to the quorum
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Debug)]
+pub struct ReferendumRules {
+    pub quorum: Percent,
+    pub approval: Percent,
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum RawOrigin {
+    // the call of a successful binding referendum
+    Referendum,
+}
+
+// ensures that the call is enacted by a successful binding referendum
+pub struct EnsureReferendum;
+
+impl<O: Into<Result<RawOrigin, O>> + From<RawOrigin>> EnsureOrigin<O> for EnsureReferendum {
+    type Success = ();
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().map(|RawOrigin::Referendum| ())
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn successful_origin() -> O {
+        O::from(RawOrigin::Referendum)
+    }
 }
@@ -1,10 +1,11 @@
-use crate as pallet_referendum;
-use frame_support::parameter_types;
+use crate::{self as pallet_referendum, EnsureReferendum, ReferendumRules};
+use frame_support::{parameter_types, traits::Contains};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    Percent,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -18,9 +19,11 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         IdentityPallet: pallet_identity::{Pallet, Call, Storage},
-        VotingPallet: pallet_voting::{Pallet, Call, Storage},
-        ReferendumPallet: pallet_referendum::{Pallet, Call, Storage},
+        VotingPallet: pallet_voting::{Pallet, Call, Storage, Event<T>},
+        ReferendumPallet: pallet_referendum::{Pallet, Call, Storage, Origin},
+        EnactedPallet: pallet_enacted::{Pallet, Call, Storage},
     }
 );
 
@@ -47,7 +50,7 @@ impl system::Config for Test {
     type BlockHashCount = BlockHashCount;
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
@@ -55,31 +58,149 @@ impl system::Config for Test {
     type OnSetCode = ();
 }
 
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = MaxLocks;
+    type Balance = u64;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
 parameter_types! {
     pub const PetitionDuration: u64 = 10;
     pub const ReferendumDuration: u64 = 20;
+    pub static RevealDuration: Option<u64> = None;
+    pub const EnactmentDelay: u64 = 5;
+    pub const MaxEnactmentsPerBlock: u32 = 2;
+    pub const PetitionDeposit: u64 = 10;
+    pub const PreimageByteDeposit: u64 = 1;
+    pub const PetitionCooldown: u64 = 5;
+    pub const MaxSuggestionLength: u32 = 64;
+    pub const ConstitutionalRules: ReferendumRules = ReferendumRules {
+        quorum: Percent::from_percent(50),
+        approval: Percent::from_percent(66),
+    };
+    pub const FiscalRules: ReferendumRules = ReferendumRules {
+        quorum: Percent::from_percent(30),
+        approval: Percent::from_percent(50),
+    };
+    pub const OtherRules: ReferendumRules = ReferendumRules {
+        quorum: Percent::from_percent(0),
+        approval: Percent::from_percent(50),
+    };
 }
 impl pallet_referendum::Config for Test {
     type PetitionDuration = PetitionDuration;
     type ReferendumDuration = ReferendumDuration;
+    type RevealDuration = RevealDuration;
+    type EnactmentDelay = EnactmentDelay;
+    type MaxEnactmentsPerBlock = MaxEnactmentsPerBlock;
+    type Origin = Origin;
+    type Proposal = Call;
+    type Currency = Balances;
+    type PetitionDeposit = PetitionDeposit;
+    type PreimageByteDeposit = PreimageByteDeposit;
+    type PetitionCooldown = PetitionCooldown;
+    type MaxSuggestionLength = MaxSuggestionLength;
+    type ConstitutionalRules = ConstitutionalRules;
+    type FiscalRules = FiscalRules;
+    type OtherRules = OtherRules;
     type IdentityTrait = IdentityPallet;
     type VotingTrait = VotingPallet;
+    type Laws = MockLaws;
+}
+
+// only the law with the hash `[1; 32]` exists
+pub struct MockLaws;
+
+impl Contains<H256> for MockLaws {
+    fn contains(law_hash: &H256) -> bool {
+        *law_hash == H256::repeat_byte(1)
+    }
 }
 
 impl pallet_identity::Config for Test {}
 
+parameter_types! {
+    pub const MaxPreferences: u32 = 16;
+    pub const MaxCandidateLength: u32 = 32;
+    pub const MaxFinalizationsPerBlock: u32 = 10;
+    pub const MaxBallotRemovalsPerBlock: u32 = 100;
+    pub const MaxDelegators: u32 = 8;
+}
+
 impl pallet_voting::Config for Test {
+    type Event = Event;
     type FinalizeVotingDispatch = ReferendumPallet;
+    type MaxPreferences = MaxPreferences;
+    type MaxCandidateLength = MaxCandidateLength;
+    type MaxFinalizationsPerBlock = MaxFinalizationsPerBlock;
+    type MaxBallotRemovalsPerBlock = MaxBallotRemovalsPerBlock;
+    type MaxDelegators = MaxDelegators;
+}
+
+impl pallet_enacted::Config for Test {
+    type ReferendumOrigin = EnsureReferendum;
+}
+
+// a pallet with the call enacted by the binding referendums
+#[frame_support::pallet]
+pub mod pallet_enacted {
+    use frame_support::pallet_prelude::*;
+    use frame_support::traits::EnsureOrigin;
+    use frame_support::weights::Weight;
+    use frame_system::pallet_prelude::*;
 
-    type FinalizeAltVotingDispatch = ();
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type ReferendumOrigin: EnsureOrigin<Self::Origin>;
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
 
-    type FinalizeAltVotingListDispatch = ();
+    #[pallet::storage]
+    #[pallet::getter(fn value)]
+    pub type Value<T> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::weight(*weight)]
+        pub fn set_value(
+            origin: OriginFor<T>,
+            value: u32,
+            weight: Weight,
+        ) -> DispatchResultWithPostInfo {
+            T::ReferendumOrigin::ensure_origin(origin)?;
+            <Value<T>>::put(value);
+            Ok(().into())
+        }
+    }
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    system::GenesisConfig::default()
+    let mut storage = system::GenesisConfig::default()
         .build_storage::<Test>()
-        .unwrap()
-        .into()
+        .unwrap();
+    // the accounts 1..=10 can afford a petition, 11 can not
+    pallet_balances::GenesisConfig::<Test> {
+        balances: (1..=10)
+            .map(|account| (account, 100))
+            .chain(Some((11, 5)))
+            .collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    storage.into()
 }
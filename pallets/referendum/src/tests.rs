@@ -1,140 +1,799 @@
 use crate::mock::*;
-//use crate::*;
-//use frame_support::{assert_err, assert_ok, traits::OnFinalize};
-//use frame_system::ensure_signed;
+// the runtime call and origin, not the ones of the pallet
+use crate::mock::{Call, Origin};
+use crate::*;
+use frame_support::{
+    assert_err, assert_ok,
+    codec::Encode,
+    traits::{Get, OnFinalize, OnInitialize},
+    weights::Weight,
+};
+use frame_system::ensure_signed;
+use pallet_identity::{IdentityTrait, IdentityType};
+use pallet_voting::{VotingRules, VotingTrait};
+use sp_core::H256;
 
-//type Hashing = <Test as frame_system::Config>::Hashing;
+// the accounts 1..=amount are the citizens with the passport ids [id; 32]
+fn create_citizens(amount: u8) {
+    for id in 1..=amount {
+        let account = Origin::signed(id as u64);
+        IdentityPallet::match_account_to_id(ensure_signed(account).unwrap(), [id; 32]);
+        IdentityPallet::push_identity([id; 32], IdentityType::Citizen).unwrap();
+    }
+}
+
+fn vote(voters: impl Iterator<Item = u64>, subject: H256, vote: ReferendumVote) {
+    for voter in voters {
+        assert_ok!(ReferendumPallet::vote(Origin::signed(voter), subject, vote));
+    }
+}
+
+fn suggestion(title: Vec<u8>, category: SuggestionCategory) -> Suggestion<H256> {
+    Suggestion {
+        title,
+        summary: vec![],
+        content_hash: H256::repeat_byte(9),
+        category,
+        law: None,
+        call: None,
+    }
+}
 
 #[test]
 fn basic_referendum_test() {
     new_test_ext().execute_with(|| {
-        // create 10 citizens
-        // let account1 = Origin::signed(1);
-        // let id1 = [1; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account1.clone()).unwrap(), id1);
-        // IdentityPallet::push_identity(id1.clone(), IdentityType::Citizen).unwrap();
-
-        // let account2 = Origin::signed(2);
-        // let id2 = [2; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account2.clone()).unwrap(), id2);
-        // IdentityPallet::push_identity(id2.clone(), IdentityType::Citizen).unwrap();
-
-        // let account3 = Origin::signed(3);
-        // let id3 = [3; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account3.clone()).unwrap(), id3);
-        // IdentityPallet::push_identity(id3.clone(), IdentityType::Citizen).unwrap();
-
-        // let account4 = Origin::signed(4);
-        // let id4 = [4; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account4.clone()).unwrap(), id4);
-        // IdentityPallet::push_identity(id4.clone(), IdentityType::Citizen).unwrap();
-
-        // let account5 = Origin::signed(5);
-        // let id5 = [5; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account5.clone()).unwrap(), id5);
-        // IdentityPallet::push_identity(id5.clone(), IdentityType::Citizen).unwrap();
-
-        // let account6 = Origin::signed(6);
-        // let id6 = [6; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account6.clone()).unwrap(), id6);
-        // IdentityPallet::push_identity(id6.clone(), IdentityType::Citizen).unwrap();
-
-        // let account7 = Origin::signed(7);
-        // let id7 = [7; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account7.clone()).unwrap(), id7);
-        // IdentityPallet::push_identity(id7.clone(), IdentityType::Citizen).unwrap();
-
-        // let account8 = Origin::signed(8);
-        // let id8 = [8; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account8.clone()).unwrap(), id8);
-        // IdentityPallet::push_identity(id8.clone(), IdentityType::Citizen).unwrap();
-
-        // let account9 = Origin::signed(9);
-        // let id9 = [9; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account9.clone()).unwrap(), id9);
-        // IdentityPallet::push_identity(id9.clone(), IdentityType::Citizen).unwrap();
-
-        // let account10 = Origin::signed(10);
-        // let id10 = [10; 32];
-        // IdentityPallet::match_account_to_id(ensure_signed(account10.clone()).unwrap(), id10);
-        // IdentityPallet::push_identity(id10.clone(), IdentityType::Citizen).unwrap();
-
-        // assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
-        // assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
-        // assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 0);
-
-        // // suggest petition
-        // let sug = Suggestion { data: vec![] };
-        // let sug_hash = Hashing::hash(&sug.data[..]);
-
-        // assert_ok!(ReferendumPallet::suggest_petition(
-        //     account1.clone(),
-        //     sug.clone()
-        // ));
-
-        // assert_eq!(ReferendumPallet::get_active_petitions().len(), 1);
-        // assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
-        // assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 0);
-
-        // assert_ok!(ReferendumPallet::vote(account1.clone(), sug_hash));
-        // assert_ok!(ReferendumPallet::vote(account2.clone(), sug_hash));
-        // VotingPallet::on_finalize(PetitionDuration::get());
-
-        // assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
-        // assert_eq!(ReferendumPallet::get_active_referendums().len(), 1);
-        // assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 0);
-
-        // assert_ok!(ReferendumPallet::vote(account1.clone(), sug_hash));
-        // assert_ok!(ReferendumPallet::vote(account2.clone(), sug_hash));
-        // assert_ok!(ReferendumPallet::vote(account3.clone(), sug_hash));
-        // assert_ok!(ReferendumPallet::vote(account4.clone(), sug_hash));
-        // assert_ok!(ReferendumPallet::vote(account5.clone(), sug_hash));
-        // assert_ok!(ReferendumPallet::vote(account6.clone(), sug_hash));
-
-        // VotingPallet::on_finalize(ReferendumDuration::get());
-
-        // assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
-        // assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
-        // assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 1);
+        create_citizens(10);
+
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+        assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 0);
+
+        // suggest petition
+        let sug = suggestion(vec![], SuggestionCategory::Fiscal);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(1),
+            sug.clone()
+        ));
+
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 1);
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+        assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 0);
+
+        // 2 of 10 citizens are more than 10%
+        vote(1..=2, sug_hash, ReferendumVote::Aye);
+        System::set_block_number(PetitionDuration::get());
+        VotingPallet::on_finalize(PetitionDuration::get());
+
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 1);
+        assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 0);
+
+        // the supporters of the petition vote in the referendum again
+        vote(1..=6, sug_hash, ReferendumVote::Aye);
+        let referendum_end = PetitionDuration::get() + ReferendumDuration::get();
+        VotingPallet::on_finalize(referendum_end);
+
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+        assert_eq!(
+            ReferendumPallet::get_successfull_referendums().get(&sug_hash),
+            Some(&sug)
+        );
+        assert!(VotingPallet::active_votings(sug_hash).is_none());
+    });
+}
+
+#[test]
+fn failed_petition_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let sug = suggestion(vec![1], SuggestionCategory::Fiscal);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+
+        // 1 of 10 citizens is not more than 10%
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(1), sug));
+        vote(1..=1, sug_hash, ReferendumVote::Aye);
+        VotingPallet::on_finalize(PetitionDuration::get());
+
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+        assert!(VotingPallet::active_votings(sug_hash).is_none());
+        assert_err!(
+            ReferendumPallet::vote(Origin::signed(2), sug_hash, ReferendumVote::Aye),
+            <Error<Test>>::SubjectDoesNotExist
+        );
+    });
+}
+
+#[test]
+fn escalation_failure_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let sug = suggestion(vec![1], SuggestionCategory::Fiscal);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(1), sug));
+        vote(1..=2, sug_hash, ReferendumVote::Aye);
+
+        // the blocks the referendum would end in have no room for it
+        System::set_block_number(PetitionDuration::get());
+        let max = MaxFinalizationsPerBlock::get() as u64;
+        for subject in 1..=max * max {
+            assert_ok!(VotingPallet::create_voting(
+                H256::from_low_u64_be(subject),
+                ReferendumDuration::get(),
+                VotingRules::open(),
+                None
+            ));
+        }
+        VotingPallet::on_finalize(PetitionDuration::get());
+
+        // the supported petition fails and its deposit is not returned
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+        assert!(VotingPallet::active_votings(sug_hash).is_none());
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 100 - PetitionDeposit::get());
+        let closed = ReferendumPallet::get_closed_suggestions(0, 1);
+        assert_eq!(closed[0].outcome, SuggestionOutcome::FailedPetition);
+    });
+}
+
+#[test]
+fn failed_referendum_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let sug = suggestion(vec![2], SuggestionCategory::Fiscal);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(1),
+            sug.clone()
+        ));
+        vote(1..=2, sug_hash, ReferendumVote::Aye);
+        VotingPallet::on_finalize(PetitionDuration::get());
+        assert_eq!(
+            ReferendumPallet::get_active_referendums().get(&sug_hash),
+            Some(&sug)
+        );
+
+        // 3 ayes of 6 votes are not more than 50%
+        vote(1..=3, sug_hash, ReferendumVote::Aye);
+        vote(4..=6, sug_hash, ReferendumVote::Nay);
+        VotingPallet::on_finalize(ReferendumDuration::get());
+
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+        assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 0);
+
+        // the failed suggestion can be petitioned again
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(2), sug));
+        vote(1..=1, sug_hash, ReferendumVote::Aye);
     });
 }
 
 #[test]
 fn referendum_error_test() {
     new_test_ext().execute_with(|| {
-        // let account1 = Origin::signed(1);
-        // let id1 = [1; 32];
-        // let sug = Suggestion { data: vec![] };
-        // let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
-
-        // assert_err!(
-        //     ReferendumPallet::suggest_petition(account1.clone(), sug.clone()),
-        //     <Error<Test>>::AccountCannotSuggestPetition
-        // );
-
-        // assert_err!(
-        //     ReferendumPallet::vote(account1.clone(), sug_hash),
-        //     <Error<Test>>::AccountCannotVote
-        // );
-
-        // IdentityPallet::match_account_to_id(ensure_signed(account1.clone()).unwrap(), id1);
-        // IdentityPallet::push_identity(id1.clone(), IdentityType::Citizen).unwrap();
-
-        // assert_err!(
-        //     ReferendumPallet::vote(account1.clone(), sug_hash),
-        //     <Error<Test>>::SubjectDoesNotExist
-        // );
-
-        // assert_ok!(ReferendumPallet::suggest_petition(
-        //     account1.clone(),
-        //     sug.clone()
-        // ));
-
-        // assert_ok!(ReferendumPallet::vote(account1.clone(), sug_hash));
-
-        // assert_err!(
-        //     ReferendumPallet::vote(account1.clone(), sug_hash),
-        //     <Error<Test>>::AlreadyVoted
-        // );
+        let account1 = Origin::signed(1);
+        let id1 = [1; 32];
+        let sug = suggestion(vec![], SuggestionCategory::Fiscal);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+
+        assert_err!(
+            ReferendumPallet::suggest_petition(account1.clone(), sug.clone()),
+            <Error<Test>>::AccountCannotSuggestPetition
+        );
+
+        assert_err!(
+            ReferendumPallet::vote(account1.clone(), sug_hash, ReferendumVote::Aye),
+            <Error<Test>>::AccountCannotVote
+        );
+
+        IdentityPallet::match_account_to_id(ensure_signed(account1.clone()).unwrap(), id1);
+        IdentityPallet::push_identity(id1, IdentityType::Citizen).unwrap();
+
+        assert_err!(
+            ReferendumPallet::vote(account1.clone(), sug_hash, ReferendumVote::Aye),
+            <Error<Test>>::SubjectDoesNotExist
+        );
+
+        assert_ok!(ReferendumPallet::suggest_petition(
+            account1.clone(),
+            sug.clone()
+        ));
+
+        assert_ok!(ReferendumPallet::vote(
+            account1.clone(),
+            sug_hash,
+            ReferendumVote::Aye
+        ));
+
+        assert_err!(
+            ReferendumPallet::vote(account1.clone(), sug_hash, ReferendumVote::Aye),
+            <Error<Test>>::AlreadyVoted
+        );
+
+        // the petition can not be suggested twice
+        assert_err!(
+            ReferendumPallet::suggest_petition(account1, sug),
+            <Error<Test>>::PetitionAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn referendum_quorum_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let sug = suggestion(vec![3], SuggestionCategory::Constitutional);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+        let amendment = suggestion(vec![4], SuggestionCategory::Constitutional);
+        let amendment_hash = ReferendumPallet::get_suggestion_hash(&amendment);
+
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(1), sug));
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(2),
+            amendment.clone()
+        ));
+        vote(1..=2, sug_hash, ReferendumVote::Aye);
+        vote(1..=2, amendment_hash, ReferendumVote::Aye);
+        VotingPallet::on_finalize(PetitionDuration::get());
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 2);
+
+        // 4 of 10 citizens do not reach the quorum of 50%
+        vote(1..=3, sug_hash, ReferendumVote::Aye);
+        vote(4..=4, sug_hash, ReferendumVote::Nay);
+        // the abstentions count to the quorum, but not to the approval
+        vote(1..=2, amendment_hash, ReferendumVote::Aye);
+        vote(3..=3, amendment_hash, ReferendumVote::Nay);
+        vote(4..=5, amendment_hash, ReferendumVote::Abstain);
+        VotingPallet::on_finalize(ReferendumDuration::get());
+
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+        assert_eq!(
+            ReferendumPallet::get_successfull_referendums(),
+            vec![(amendment_hash, amendment)].into_iter().collect()
+        );
+    });
+}
+
+#[test]
+fn petition_abstain_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let sug = suggestion(vec![5], SuggestionCategory::Other);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+
+        // only the ayes support a petition
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(1), sug));
+        vote(1..=1, sug_hash, ReferendumVote::Aye);
+        vote(2..=3, sug_hash, ReferendumVote::Nay);
+        vote(4..=5, sug_hash, ReferendumVote::Abstain);
+        VotingPallet::on_finalize(PetitionDuration::get());
+
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 0);
+    });
+}
+
+#[test]
+fn binding_referendum_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let call = Call::EnactedPallet(pallet_enacted::Call::set_value(42, 1)).encode();
+        let call_hash = ReferendumPallet::preimage_hash(&call);
+        let sug = Suggestion {
+            call: Some(call_hash),
+            ..suggestion(vec![6], SuggestionCategory::Fiscal)
+        };
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+
+        assert_err!(
+            ReferendumPallet::note_preimage(Origin::signed(1), vec![255]),
+            <Error<Test>>::InvalidCall
+        );
+        assert_err!(
+            ReferendumPallet::note_preimage(Origin::signed(1), vec![0; 65]),
+            <Error<Test>>::SuggestionTooLong
+        );
+        assert_err!(
+            ReferendumPallet::suggest_petition(Origin::signed(1), sug.clone()),
+            <Error<Test>>::PreimageNotFound
+        );
+        // a call heavier than a block is not suggested
+        let heavy =
+            Call::EnactedPallet(pallet_enacted::Call::set_value(42, Weight::max_value())).encode();
+        assert_ok!(ReferendumPallet::note_preimage(
+            Origin::signed(1),
+            heavy.clone()
+        ));
+        assert_err!(
+            ReferendumPallet::suggest_petition(
+                Origin::signed(1),
+                Suggestion {
+                    call: Some(ReferendumPallet::preimage_hash(&heavy)),
+                    ..sug.clone()
+                }
+            ),
+            <Error<Test>>::CallTooHeavy
+        );
+        // the call can be dispatched by the referendum only
+        assert_err!(
+            EnactedPallet::set_value(Origin::signed(1), 42, 1),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(ReferendumPallet::note_preimage(
+            Origin::signed(1),
+            call.clone()
+        ));
+        assert_err!(
+            ReferendumPallet::note_preimage(Origin::signed(2), call),
+            <Error<Test>>::PreimageAlreadyNoted
+        );
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(1), sug));
+        assert_err!(
+            ReferendumPallet::unnote_preimage(Origin::signed(1), call_hash),
+            <Error<Test>>::PreimageInUse
+        );
+        vote(1..=2, sug_hash, ReferendumVote::Aye);
+        System::set_block_number(PetitionDuration::get());
+        VotingPallet::on_finalize(PetitionDuration::get());
+        vote(1..=6, sug_hash, ReferendumVote::Aye);
+        let referendum_end = PetitionDuration::get() + ReferendumDuration::get();
+        System::set_block_number(referendum_end);
+        VotingPallet::on_finalize(referendum_end);
+
+        // the call is dispatched after the enactment delay with its weight
+        assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 1);
+        ReferendumPallet::on_initialize(referendum_end + EnactmentDelay::get() - 1);
+        assert_eq!(EnactedPallet::value(), 0);
+        assert_eq!(
+            ReferendumPallet::on_initialize(referendum_end + EnactmentDelay::get()),
+            1
+        );
+        assert_eq!(EnactedPallet::value(), 42);
+
+        // the enacted call is removed with its deposit returned,
+        // the unused call is removed by the depositor
+        assert!(ReferendumPallet::preimage(call_hash).is_none());
+        let heavy_hash = ReferendumPallet::preimage_hash(&heavy);
+        let heavy_deposit = heavy.len() as u64 * PreimageByteDeposit::get();
+        assert_eq!(Balances::reserved_balance(1), heavy_deposit);
+        assert_err!(
+            ReferendumPallet::unnote_preimage(Origin::signed(2), heavy_hash),
+            <Error<Test>>::NotPreimageDepositor
+        );
+        assert_ok!(ReferendumPallet::unnote_preimage(
+            Origin::signed(1),
+            heavy_hash
+        ));
+        assert_eq!(Balances::reserved_balance(1), 0);
+    });
+}
+
+#[test]
+fn preimage_deposit_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let call = Call::EnactedPallet(pallet_enacted::Call::set_value(7, 1)).encode();
+        let call_hash = ReferendumPallet::preimage_hash(&call);
+        let deposit = call.len() as u64 * PreimageByteDeposit::get();
+        assert_ok!(ReferendumPallet::note_preimage(
+            Origin::signed(2),
+            call.clone()
+        ));
+        assert_eq!(Balances::reserved_balance(2), deposit);
+
+        // two suggestions refer to the call
+        let suggestions: Vec<_> = [1, 2]
+            .iter()
+            .map(|title| Suggestion {
+                call: Some(call_hash),
+                ..suggestion(vec![*title], SuggestionCategory::Other)
+            })
+            .collect();
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(1),
+            suggestions[0].clone()
+        ));
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(3),
+            suggestions[1].clone()
+        ));
+
+        // the failed petition releases the call, the referendum still refers to it
+        let referendum = ReferendumPallet::get_suggestion_hash(&suggestions[1]);
+        vote(1..=2, referendum, ReferendumVote::Aye);
+        System::set_block_number(PetitionDuration::get());
+        VotingPallet::on_finalize(PetitionDuration::get());
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 1);
+        assert!(ReferendumPallet::preimage(call_hash).is_some());
+        assert_err!(
+            ReferendumPallet::unnote_preimage(Origin::signed(2), call_hash),
+            <Error<Test>>::PreimageInUse
+        );
+
+        // the failed referendum releases the call and the deposit is returned
+        let referendum_end = PetitionDuration::get() + ReferendumDuration::get();
+        System::set_block_number(referendum_end);
+        VotingPallet::on_finalize(referendum_end);
+        assert!(ReferendumPallet::preimage(call_hash).is_none());
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_err!(
+            ReferendumPallet::unnote_preimage(Origin::signed(2), call_hash),
+            <Error<Test>>::PreimageNotFound
+        );
+    });
+}
+
+#[test]
+fn enactment_queue_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        // two heavy calls do not fit into a block together
+        let heavy = <Test as frame_system::Config>::BlockWeights::get().max_block / 2 + 1;
+        let calls = [(1, heavy), (2, heavy), (3, 1)];
+        let mut subjects = vec![];
+        for (account, (value, weight)) in (1..).zip(calls.iter()) {
+            let call =
+                Call::EnactedPallet(pallet_enacted::Call::set_value(*value, *weight)).encode();
+            let sug = Suggestion {
+                call: Some(ReferendumPallet::preimage_hash(&call)),
+                ..suggestion(vec![*value as u8], SuggestionCategory::Other)
+            };
+            assert_ok!(ReferendumPallet::note_preimage(
+                Origin::signed(account),
+                call
+            ));
+            assert_ok!(ReferendumPallet::suggest_petition(
+                Origin::signed(account),
+                sug.clone()
+            ));
+            subjects.push(ReferendumPallet::get_suggestion_hash(&sug));
+        }
+        for subject in subjects.iter() {
+            vote(1..=2, *subject, ReferendumVote::Aye);
+        }
+        System::set_block_number(PetitionDuration::get());
+        VotingPallet::on_finalize(PetitionDuration::get());
+        for subject in subjects.iter() {
+            vote(1..=6, *subject, ReferendumVote::Aye);
+        }
+        let referendum_end = PetitionDuration::get() + ReferendumDuration::get();
+        System::set_block_number(referendum_end);
+        VotingPallet::on_finalize(referendum_end);
+        assert_eq!(ReferendumPallet::get_successfull_referendums().len(), 3);
+
+        // two referendums are enacted in a block, the second heavy call waits for the
+        // next block too
+        let enactment = referendum_end + EnactmentDelay::get();
+        assert_eq!(ReferendumPallet::on_initialize(enactment), heavy);
+        assert_eq!(EnactedPallet::value(), 1);
+        assert_eq!(ReferendumPallet::on_initialize(enactment + 1), 1 + heavy);
+        assert_eq!(EnactedPallet::value(), 2);
+    });
+}
+
+#[test]
+fn petition_deposit_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        IdentityPallet::match_account_to_id(11, [11; 32]);
+        IdentityPallet::push_identity([11; 32], IdentityType::Citizen).unwrap();
+        let sug = suggestion(vec![7], SuggestionCategory::Other);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+        let other = suggestion(vec![8], SuggestionCategory::Other);
+        let other_hash = ReferendumPallet::get_suggestion_hash(&other);
+
+        assert_err!(
+            ReferendumPallet::suggest_petition(Origin::signed(11), sug.clone()),
+            <Error<Test>>::InsufficientDeposit
+        );
+        assert_err!(
+            ReferendumPallet::suggest_petition(
+                Origin::signed(1),
+                suggestion(vec![0; 65], SuggestionCategory::Other)
+            ),
+            <Error<Test>>::SuggestionTooLong
+        );
+        assert_err!(
+            ReferendumPallet::suggest_petition(
+                Origin::signed(1),
+                suggestion(vec![0xff], SuggestionCategory::Other)
+            ),
+            <Error<Test>>::InvalidText
+        );
+
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(1),
+            sug.clone()
+        ));
+        assert_eq!(Balances::reserved_balance(1), PetitionDeposit::get());
+        assert_err!(
+            ReferendumPallet::suggest_petition(Origin::signed(2), sug),
+            <Error<Test>>::PetitionAlreadyExists
+        );
+        // a citizen waits for the cooldown between the petitions
+        assert_err!(
+            ReferendumPallet::suggest_petition(Origin::signed(1), other.clone()),
+            <Error<Test>>::PetitionCooldown
+        );
+        System::set_block_number(PetitionCooldown::get());
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(1), other));
+        assert_eq!(Balances::reserved_balance(1), 2 * PetitionDeposit::get());
+
+        // the deposit of the supported petition is returned
+        vote(1..=2, sug_hash, ReferendumVote::Aye);
+        vote(1..=1, other_hash, ReferendumVote::Aye);
+        VotingPallet::on_finalize(PetitionDuration::get());
+        assert_eq!(ReferendumPallet::get_active_referendums().len(), 1);
+        assert_eq!(Balances::reserved_balance(1), PetitionDeposit::get());
+
+        // and the deposit of the rejected one is slashed
+        VotingPallet::on_finalize(PetitionCooldown::get() + PetitionDuration::get());
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 0);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 100 - PetitionDeposit::get());
+    });
+}
+
+#[test]
+fn archive_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let suggestions: Vec<_> = (9..12)
+            .map(|data| suggestion(vec![data], SuggestionCategory::Fiscal))
+            .collect();
+        let hashes: Vec<_> = suggestions
+            .iter()
+            .map(ReferendumPallet::get_suggestion_hash)
+            .collect();
+        for (account, sug) in suggestions.iter().enumerate() {
+            assert_ok!(ReferendumPallet::suggest_petition(
+                Origin::signed(account as u64 + 1),
+                sug.clone()
+            ));
+        }
+
+        vote(1..=1, hashes[0], ReferendumVote::Aye);
+        vote(1..=2, hashes[1], ReferendumVote::Aye);
+        vote(1..=2, hashes[2], ReferendumVote::Aye);
+        vote(3..=3, hashes[2], ReferendumVote::Nay);
+        System::set_block_number(PetitionDuration::get());
+        VotingPallet::on_finalize(PetitionDuration::get());
+
+        // the failed petition is archived at once
+        assert_eq!(ReferendumPallet::get_closed_suggestions_count(), 1);
+        assert_eq!(
+            ReferendumPallet::get_closed_suggestions(0, 10),
+            vec![ClosedSuggestion {
+                subject: hashes[0],
+                suggestion: suggestions[0].clone(),
+                outcome: SuggestionOutcome::FailedPetition,
+                petition_tally: Tally {
+                    ayes: 1,
+                    nays: 0,
+                    abstentions: 0
+                },
+                referendum_tally: None,
+                opened: 0,
+                closed: PetitionDuration::get(),
+            }]
+        );
+
+        vote(1..=3, hashes[1], ReferendumVote::Aye);
+        vote(4..=6, hashes[1], ReferendumVote::Nay);
+        vote(1..=6, hashes[2], ReferendumVote::Aye);
+        let referendum_end = PetitionDuration::get() + ReferendumDuration::get();
+        VotingPallet::on_finalize(referendum_end);
+
+        assert_eq!(ReferendumPallet::get_closed_suggestions_count(), 3);
+        assert_eq!(ReferendumPallet::get_closed_suggestions(0, 2).len(), 2);
+        assert_eq!(ReferendumPallet::get_closed_suggestions(5, 2), vec![]);
+        let referendums = ReferendumPallet::get_closed_suggestions(1, 10);
+        assert_eq!(referendums.len(), 2);
+        let passed = referendums
+            .iter()
+            .find(|closed| closed.subject == hashes[2])
+            .unwrap();
+        assert_eq!(passed.outcome, SuggestionOutcome::Passed);
+        assert_eq!(passed.petition_tally.nays, 1);
+        assert_eq!(passed.referendum_tally.map(|tally| tally.ayes), Some(6));
+        assert_eq!((passed.opened, passed.closed), (0, referendum_end));
+        let failed = referendums
+            .iter()
+            .find(|closed| closed.subject == hashes[1])
+            .unwrap();
+        assert_eq!(failed.outcome, SuggestionOutcome::FailedReferendum);
+        assert_eq!(failed.referendum_tally.map(|tally| tally.nays), Some(3));
+    });
+}
+
+#[test]
+fn suggestion_serde_test() {
+    let categories = [
+        (SuggestionCategory::Constitutional, "\"Constitutional\""),
+        (SuggestionCategory::Fiscal, "\"Fiscal\""),
+        (SuggestionCategory::Other, "\"Other\""),
+    ];
+    for (category, name) in categories.iter() {
+        assert_eq!(serde_json::to_string(category).unwrap(), *name);
+    }
+    let sug = Suggestion {
+        law: Some(H256::repeat_byte(1)),
+        ..suggestion(b"budget".to_vec(), SuggestionCategory::Fiscal)
+    };
+    let json = serde_json::to_value(&sug).unwrap();
+    assert_eq!(json["category"], "Fiscal");
+    assert_eq!(
+        serde_json::from_value::<Suggestion<H256>>(json).unwrap(),
+        sug
+    );
+}
+
+#[test]
+fn structured_suggestion_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        let amendment = Suggestion {
+            summary: b"repeals the law".to_vec(),
+            law: Some(H256::repeat_byte(1)),
+            ..suggestion(b"amendment".to_vec(), SuggestionCategory::Other)
+        };
+        let amendment_hash = ReferendumPallet::get_suggestion_hash(&amendment);
+
+        assert_err!(
+            ReferendumPallet::suggest_petition(
+                Origin::signed(1),
+                Suggestion {
+                    law: Some(H256::repeat_byte(2)),
+                    ..amendment.clone()
+                }
+            ),
+            <Error<Test>>::LawDoesNotExist
+        );
+        assert_err!(
+            ReferendumPallet::suggest_petition(
+                Origin::signed(1),
+                Suggestion {
+                    summary: vec![0; 65],
+                    ..amendment.clone()
+                }
+            ),
+            <Error<Test>>::SuggestionTooLong
+        );
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(1),
+            amendment.clone()
+        ));
+        assert_eq!(
+            ReferendumPallet::get_suggestion(amendment_hash),
+            Some(amendment.clone())
+        );
+
+        // the suggestions with the same title are different subjects
+        let other = Suggestion {
+            content_hash: H256::repeat_byte(3),
+            ..amendment
+        };
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(2), other));
+        assert_eq!(ReferendumPallet::get_active_petitions().len(), 2);
+    });
+}
+
+#[test]
+fn secret_vote_test() {
+    new_test_ext().execute_with(|| {
+        RevealDuration::set(Some(5));
+        create_citizens(10);
+        let sug = suggestion(vec![], SuggestionCategory::Fiscal);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+        assert_ok!(ReferendumPallet::suggest_petition(Origin::signed(1), sug));
+
+        let salt = b"salt".to_vec();
+        let commitment = |account: u64| {
+            VotingPallet::commitment_of(
+                &account,
+                sug_hash,
+                &pallet_voting::AltVote::default(),
+                &ReferendumPallet::secret_salt(ReferendumVote::Aye, salt.clone()),
+            )
+        };
+        assert_ok!(ReferendumPallet::commit_vote(
+            Origin::signed(1),
+            sug_hash,
+            commitment(1)
+        ));
+        // 2 copies the commitment of 1
+        assert_ok!(ReferendumPallet::commit_vote(
+            Origin::signed(2),
+            sug_hash,
+            commitment(1)
+        ));
+
+        System::set_block_number(System::block_number() + PetitionDuration::get() + 1);
+        assert_ok!(ReferendumPallet::reveal_vote(
+            Origin::signed(1),
+            sug_hash,
+            ReferendumVote::Aye,
+            salt.clone()
+        ));
+        assert_err!(
+            ReferendumPallet::reveal_vote(Origin::signed(2), sug_hash, ReferendumVote::Aye, salt),
+            pallet_voting::Error::<Test>::CommitmentMismatch
+        );
+    });
+}
+
+#[test]
+fn delegated_vote_test() {
+    new_test_ext().execute_with(|| {
+        create_citizens(10);
+        System::set_block_number(1);
+        let sug = suggestion(b"delegated".to_vec(), SuggestionCategory::Other);
+        let sug_hash = ReferendumPallet::get_suggestion_hash(&sug);
+
+        assert_err!(
+            ReferendumPallet::delegate_vote(Origin::signed(3), 20),
+            <Error<Test>>::DelegateIsNotCitizen
+        );
+        // 3 -> 4 -> 5, 6 -> 5, 7 -> 8
+        for (voter, delegate) in &[(3, 4), (4, 5), (6, 5), (7, 8)] {
+            assert_ok!(ReferendumPallet::delegate_vote(
+                Origin::signed(*voter),
+                *delegate
+            ));
+        }
+        assert_err!(
+            ReferendumPallet::delegate_vote(Origin::signed(5), 3),
+            pallet_voting::Error::<Test>::DelegationCycle
+        );
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(mock::Event::pallet_voting(pallet_voting::Event::Delegated(
+                DelegationTopic::Referendums,
+                7,
+                8
+            )))
+        );
+
+        assert_ok!(ReferendumPallet::suggest_petition(
+            Origin::signed(1),
+            sug.clone()
+        ));
+        // the delegates do not vote for the supporters of the petitions
+        vote(1..=2, sug_hash, ReferendumVote::Aye);
+        System::set_block_number(PetitionDuration::get());
+        VotingPallet::on_finalize(PetitionDuration::get());
+
+        // 5 votes for 3, 4 and 6
+        vote(5..=5, sug_hash, ReferendumVote::Aye);
+        // the vote of 4 replaces the vote of 5 for 4 and 3
+        vote(4..=4, sug_hash, ReferendumVote::Nay);
+        vote(3..=3, sug_hash, ReferendumVote::Abstain);
+        assert_err!(
+            ReferendumPallet::vote(Origin::signed(4), sug_hash, ReferendumVote::Aye),
+            <Error<Test>>::AlreadyVoted
+        );
+        // the delegations made after 5 voted apply to the later votes only,
+        // 9 votes itself and 10 does not vote
+        assert_ok!(ReferendumPallet::delegate_vote(Origin::signed(9), 5));
+        assert_ok!(ReferendumPallet::delegate_vote(Origin::signed(10), 5));
+        vote(9..=9, sug_hash, ReferendumVote::Aye);
+        assert_ok!(ReferendumPallet::undelegate_vote(Origin::signed(7)));
+        vote(8..=8, sug_hash, ReferendumVote::Nay);
+
+        let referendum_end = PetitionDuration::get() + ReferendumDuration::get();
+        VotingPallet::on_finalize(referendum_end);
+        let closed = &ReferendumPallet::get_closed_suggestions(0, 1)[0];
+        assert_eq!(
+            closed.referendum_tally,
+            Some(Tally {
+                ayes: 3,
+                nays: 2,
+                abstentions: 1
+            })
+        );
     });
 }
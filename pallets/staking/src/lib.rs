@@ -835,6 +835,9 @@ pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 
     /// Weight information for extrinsics in this pallet.
     type WeightInfo: WeightInfo;
+
+    /// Handler for the decrease of the liber stake of a stash.
+    type OnLiberStakeChanged: OnLiberStakeChanged<Self::AccountId>;
 }
 
 /// Mode of era-forcing.
@@ -1642,6 +1645,7 @@ decl_module! {
             let controller = ensure_signed(origin)?;
             let mut ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
             let (stash, old_total) = (ledger.stash.clone(), ledger.total);
+            let old_liber_amount = ledger.liber_amount;
             if let Some(current_era) = Self::current_era() {
                 ledger = ledger.consolidate_unlocked(current_era)
             }
@@ -1681,7 +1685,11 @@ decl_module! {
             if ledger.total < old_total {
                 // Already checked that this won't overflow by entry condition.
                 let value = old_total - ledger.total;
-                Self::deposit_event(RawEvent::Withdrawn(stash, value));
+                Self::deposit_event(RawEvent::Withdrawn(stash.clone(), value));
+            }
+
+            if ledger.liber_amount < old_liber_amount {
+                T::OnLiberStakeChanged::on_liber_stake_changed(&stash);
             }
 
             Ok(post_info_weight.into())
@@ -3225,6 +3233,16 @@ fn is_sorted_and_unique(list: &[u32]) -> bool {
     list.windows(2).all(|w| w[0] < w[1])
 }
 
+/// Something that reacts to the decrease of the liber stake of a stash, e.g. the support
+/// given with the liber stake.
+pub trait OnLiberStakeChanged<AccountId> {
+    fn on_liber_stake_changed(stash: &AccountId);
+}
+
+impl<AccountId> OnLiberStakeChanged<AccountId> for () {
+    fn on_liber_stake_changed(_: &AccountId) {}
+}
+
 pub trait StakingTrait<T: Config> {
     fn get_liber_amount(acount_id: T::AccountId) -> BalanceOf<T>;
 }
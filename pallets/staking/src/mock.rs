@@ -263,6 +263,7 @@ impl Config for Test {
     type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
     type ElectionProvider = onchain::OnChainSequentialPhragmen<Self>;
     type WeightInfo = ();
+    type OnLiberStakeChanged = ();
 }
 
 impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
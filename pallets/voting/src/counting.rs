@@ -0,0 +1,375 @@
+// Counting rules for the ranked (alternative vote) ballots.
+//
+// A ballot always counts for its highest ranked candidate that is still in the count, so
+// preferences for candidates that are unknown, repeated or already eliminated are skipped.
+// A ballot that has no continuing candidate left is exhausted and no longer takes part in
+// the count.
+//
+// Ties for the last place are broken deterministically:
+// 1. the tied candidate with the fewest votes in the most recent earlier round where the tied
+//    candidates had different tallies is eliminated (backwards tie-breaking);
+// 2. if the candidates were tied in every round, the candidate with the greatest
+//    `tie_break_key` is eliminated. The key hashes the candidate together with the seed the
+//    voting fixed when it was created, so the candidates can not pick their ids to win the ties.
+// Ties for the first place are broken the same way in favour of the candidate with the most
+// votes in the earlier rounds, then of the candidate with the least key.
+use crate::{AltVote, Candidate};
+use frame_support::codec::{Decode, Encode};
+use frame_support::sp_io::hashing::blake2_256;
+use frame_support::sp_runtime::helpers_128bit::multiply_by_rational;
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec::Vec,
+};
+
+pub type Tallies = BTreeMap<Candidate, u64>;
+
+// The seed of the final tie-breaking, the votings take the parent hash of the block in which
+// they are created.
+pub type TieBreakSeed = [u8; 32];
+
+// Fixed point precision of the ballot values in a single transferable vote count,
+// the ballots keep 9 decimal places of their value after the surplus transfers.
+const VALUE_PRECISION: u128 = 1_000_000_000;
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum CountAction {
+    Elected(Candidate),
+    Eliminated(Candidate),
+}
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct CountRound {
+    // votes of the continuing candidates at the start of the round
+    #[cfg_attr(feature = "std", serde(with = "tallies_as_pairs"))]
+    pub tallies: Tallies,
+    pub action: CountAction,
+    // votes received by every candidate from the ballots moved by the action
+    #[cfg_attr(feature = "std", serde(with = "tallies_as_pairs"))]
+    pub transfers: Tallies,
+    // votes of the moved ballots which have no continuing preference left
+    pub exhausted: u64,
+}
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug, Default)]
+pub struct CountResult {
+    #[cfg_attr(feature = "std", serde(with = "tallies_as_pairs"))]
+    pub winners: Tallies,
+    pub rounds: Vec<CountRound>,
+}
+
+// JSON maps need string keys, so the tallies are serialized as the list of the
+// (candidate, votes) pairs
+#[cfg(feature = "std")]
+mod tallies_as_pairs {
+    use super::{Candidate, Tallies};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(tallies: &Tallies, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(tallies.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Tallies, D::Error> {
+        Vec::<(Candidate, u64)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+    }
+}
+
+// Instant-runoff voting with a single winner.
+//
+// In every round the candidate with the majority of the continuing (not exhausted) votes wins,
+// otherwise the last placed candidate is eliminated and its ballots are transferred to their
+// next continuing preference. Returns `None` if there are no candidates or no valid ballots.
+pub fn instant_runoff(
+    candidates: &BTreeSet<Candidate>,
+    ballots: &[(AltVote, u64)],
+    seed: &TieBreakSeed,
+) -> Option<Candidate> {
+    eliminate_to(candidates, 1, ballots, seed)
+        .winners
+        .into_iter()
+        .find(|(_, votes)| *votes > 0)
+        .map(|(winner, _)| winner)
+}
+
+// Eliminates the last placed candidates one by one, with the same transfer and tie-breaking
+// rules as `instant_runoff`, until only `winners_amount` candidates are left.
+// The winners are the remaining candidates with their final tallies. With a single winner the
+// count stops as soon as a candidate has the majority of the continuing votes.
+pub fn eliminate_to(
+    candidates: &BTreeSet<Candidate>,
+    winners_amount: u32,
+    ballots: &[(AltVote, u64)],
+    seed: &TieBreakSeed,
+) -> CountResult {
+    let mut continuing = candidates.clone();
+    let mut tallies = tally(&continuing, ballots);
+    let mut history: Vec<Tallies> = Vec::new();
+    let mut rounds = Vec::new();
+
+    while tallies.len() > winners_amount as usize {
+        if winners_amount == 1 {
+            if let Some((leader, votes)) = majority(&tallies) {
+                let mut winners = Tallies::new();
+                winners.insert(leader, votes);
+                return CountResult { winners, rounds };
+            }
+        }
+        let eliminated = match last_placed(&tallies, &history, seed) {
+            Some(eliminated) => eliminated,
+            None => break,
+        };
+        continuing.remove(&eliminated);
+        let next_tallies = tally(&continuing, ballots);
+
+        let transfers: Tallies = next_tallies
+            .iter()
+            .filter_map(|(candidate, votes)| {
+                let before = tallies.get(candidate).copied().unwrap_or_default();
+                if *votes > before {
+                    Some((candidate.clone(), votes - before))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let transferred = transfers
+            .values()
+            .fold(0_u64, |sum, votes| sum.saturating_add(*votes));
+        let moved = tallies.get(&eliminated).copied().unwrap_or_default();
+
+        rounds.push(CountRound {
+            tallies: tallies.clone(),
+            action: CountAction::Eliminated(eliminated),
+            transfers,
+            exhausted: moved.saturating_sub(transferred),
+        });
+        history.push(tallies);
+        tallies = next_tallies;
+    }
+
+    CountResult {
+        winners: tallies,
+        rounds,
+    }
+}
+
+// Single transferable vote for `seats` winners.
+//
+// The quota is the Droop quota, `votes / (seats + 1) + 1`, where `votes` is the power of all
+// the ballots naming at least one candidate. In every round the first placed candidate is
+// elected if it reaches the quota, and the ballots it holds are transferred to their next
+// continuing preference with their value reduced by `surplus / votes` (Gregory method).
+// Otherwise the last placed candidate is eliminated and its ballots are transferred with
+// their current value. Once the continuing candidates fit into the remaining seats they are
+// elected without reaching the quota.
+pub fn single_transferable_vote(
+    candidates: &BTreeSet<Candidate>,
+    seats: u32,
+    ballots: &[(AltVote, u64)],
+    seed: &TieBreakSeed,
+) -> CountResult {
+    let mut hopeful = candidates.clone();
+    let mut values: Vec<u128> = ballots
+        .iter()
+        .map(|(_, power)| *power as u128 * VALUE_PRECISION)
+        .collect();
+    let (mut tallies, mut holders) = weighted_tally(&hopeful, ballots, &values);
+    let votes: u128 = tallies.values().sum::<u128>() / VALUE_PRECISION;
+    let quota = (votes / (seats as u128 + 1) + 1) * VALUE_PRECISION;
+
+    let mut history: Vec<BTreeMap<Candidate, u128>> = Vec::new();
+    let mut winners = Tallies::new();
+    let mut rounds = Vec::new();
+
+    while (winners.len() as u32) < seats && !hopeful.is_empty() {
+        let vacancies = seats as usize - winners.len();
+        let (candidate, action) = match first_placed(&tallies, &history, seed) {
+            Some(elected)
+                if tallies.get(&elected).copied().unwrap_or_default() >= quota
+                    || hopeful.len() <= vacancies =>
+            {
+                let votes = tallies.get(&elected).copied().unwrap_or_default();
+                let surplus = votes.saturating_sub(quota);
+                values
+                    .iter_mut()
+                    .zip(holders.iter())
+                    .filter(|(_, holder)| holder.as_ref() == Some(&elected))
+                    .for_each(|(value, _)| {
+                        *value = multiply_by_rational(*value, surplus, votes).unwrap_or_default();
+                    });
+                winners.insert(elected.clone(), whole_votes(votes));
+                (elected.clone(), CountAction::Elected(elected))
+            }
+            _ => match last_placed(&tallies, &history, seed) {
+                Some(eliminated) => (eliminated.clone(), CountAction::Eliminated(eliminated)),
+                None => break,
+            },
+        };
+
+        let moved: u128 = values
+            .iter()
+            .zip(holders.iter())
+            .filter(|(_, holder)| holder.as_ref() == Some(&candidate))
+            .map(|(value, _)| *value)
+            .sum();
+        hopeful.remove(&candidate);
+        let (next_tallies, next_holders) = weighted_tally(&hopeful, ballots, &values);
+
+        let mut transferred: u128 = 0;
+        let transfers: Tallies = next_tallies
+            .iter()
+            .filter_map(|(candidate, votes)| {
+                let before = tallies.get(candidate).copied().unwrap_or_default();
+                if *votes > before {
+                    transferred += votes - before;
+                    Some((candidate.clone(), whole_votes(votes - before)))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        rounds.push(CountRound {
+            tallies: tallies
+                .iter()
+                .map(|(candidate, votes)| (candidate.clone(), whole_votes(*votes)))
+                .collect(),
+            action,
+            transfers,
+            exhausted: whole_votes(moved.saturating_sub(transferred)),
+        });
+        history.push(tallies);
+        tallies = next_tallies;
+        holders = next_holders;
+    }
+
+    CountResult { winners, rounds }
+}
+
+// Counts every ballot for its highest ranked continuing candidate.
+pub fn tally(continuing: &BTreeSet<Candidate>, ballots: &[(AltVote, u64)]) -> Tallies {
+    let mut tallies: Tallies = continuing
+        .iter()
+        .map(|candidate| (candidate.clone(), 0))
+        .collect();
+    ballots.iter().for_each(|(ballot, power)| {
+        if let Some(votes) = ballot
+            .content
+            .iter()
+            .find(|candidate| continuing.contains(*candidate))
+            .and_then(|candidate| tallies.get_mut(candidate))
+        {
+            *votes = votes.saturating_add(*power);
+        }
+    });
+    tallies
+}
+
+// Counts the current value of every ballot for its highest ranked continuing candidate,
+// also returns the candidate holding each ballot.
+fn weighted_tally(
+    continuing: &BTreeSet<Candidate>,
+    ballots: &[(AltVote, u64)],
+    values: &[u128],
+) -> (BTreeMap<Candidate, u128>, Vec<Option<Candidate>>) {
+    let mut tallies: BTreeMap<Candidate, u128> = continuing
+        .iter()
+        .map(|candidate| (candidate.clone(), 0))
+        .collect();
+    let holders = ballots
+        .iter()
+        .zip(values.iter())
+        .map(|((ballot, _), value)| {
+            let holder = ballot
+                .content
+                .iter()
+                .find(|candidate| continuing.contains(*candidate))?;
+            if let Some(votes) = tallies.get_mut(holder) {
+                *votes = votes.saturating_add(*value);
+            }
+            Some(holder.clone())
+        })
+        .collect();
+    (tallies, holders)
+}
+
+// The candidate with more than half of the votes in the tallies, if any.
+fn majority(tallies: &Tallies) -> Option<(Candidate, u64)> {
+    let total = tallies
+        .values()
+        .fold(0_u64, |sum, votes| sum.saturating_add(*votes));
+    tallies
+        .iter()
+        .find(|(_, votes)| (**votes as u128) * 2 > total as u128)
+        .map(|(candidate, votes)| (candidate.clone(), *votes))
+}
+
+fn whole_votes(value: u128) -> u64 {
+    (value / VALUE_PRECISION).min(u64::MAX as u128) as u64
+}
+
+// Picks the candidate to eliminate from the current round's tallies, see the tie-breaking
+// rules at the top of the module. `history` holds the tallies of the earlier rounds.
+pub fn last_placed<V: Ord + Copy + Default>(
+    tallies: &BTreeMap<Candidate, V>,
+    history: &[BTreeMap<Candidate, V>],
+    seed: &TieBreakSeed,
+) -> Option<Candidate> {
+    let lowest = *tallies.values().min()?;
+    let mut tied: Vec<&Candidate> = tallies
+        .iter()
+        .filter(|(_, votes)| **votes == lowest)
+        .map(|(candidate, _)| candidate)
+        .collect();
+
+    for round in history.iter().rev() {
+        if tied.len() < 2 {
+            break;
+        }
+        let votes_in = |candidate: &Candidate| round.get(candidate).copied().unwrap_or_default();
+        if let Some(lowest) = tied.iter().map(|candidate| votes_in(candidate)).min() {
+            tied.retain(|candidate| votes_in(candidate) == lowest);
+        }
+    }
+
+    tied.into_iter()
+        .max_by_key(|candidate| tie_break_key(seed, candidate))
+        .cloned()
+}
+
+// Picks the candidate with the most votes, ties are broken in the opposite direction
+// to `last_placed`.
+pub fn first_placed<V: Ord + Copy + Default>(
+    tallies: &BTreeMap<Candidate, V>,
+    history: &[BTreeMap<Candidate, V>],
+    seed: &TieBreakSeed,
+) -> Option<Candidate> {
+    let highest = *tallies.values().max()?;
+    let mut tied: Vec<&Candidate> = tallies
+        .iter()
+        .filter(|(_, votes)| **votes == highest)
+        .map(|(candidate, _)| candidate)
+        .collect();
+
+    for round in history.iter().rev() {
+        if tied.len() < 2 {
+            break;
+        }
+        let votes_in = |candidate: &Candidate| round.get(candidate).copied().unwrap_or_default();
+        if let Some(highest) = tied.iter().map(|candidate| votes_in(candidate)).max() {
+            tied.retain(|candidate| votes_in(candidate) == highest);
+        }
+    }
+
+    tied.into_iter()
+        .min_by_key(|candidate| tie_break_key(seed, candidate))
+        .cloned()
+}
+
+// The order of the candidates tied in every round, the hash of the seed and the candidate.
+pub fn tie_break_key(seed: &TieBreakSeed, candidate: &Candidate) -> [u8; 32] {
+    blake2_256(&(seed, candidate).encode())
+}
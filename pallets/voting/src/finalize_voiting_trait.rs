@@ -1,86 +1,50 @@
 use crate::*;
 
-// a dispatchable trait for the other pallets who wants to do some actions after voting will finish
+// a dispatchable trait for the other pallets who wants to do some actions after voting will finish,
+// every voting of the registry is dispatched to all of them, so they have to check the subject
 pub trait FinalizeVotingDispatchTrait<T: Config> {
-    fn finalize_voting(subject: T::Hash, voting_setting: VotingSettings<T::BlockNumber>);
-}
-
-pub trait FinilizeAltVotingDispatchTrait<T: Config> {
-    fn finalize_voting(
-        subject: T::Hash,
-        voting_setting: AltVoutingSettings<T::BlockNumber>,
-        winner: Candidate,
-    );
-}
-//pub type AccountIdAndBallot <T: Config> = BTreeMap<T::AccountId, (AltVote, u64)>;
-#[allow(clippy::type_complexity)]
-pub trait FinalizeAltVotingListDispatchTrait<T: Config> {
     fn finalize_voting(
         subject: T::Hash,
-        voting_settings: AltVotingListSettings<T::BlockNumber>,
-        winners: BTreeMap<Candidate, u64>,
-        ballots_storage: BTreeMap<T::Hash, BTreeMap<T::AccountId, (AltVote, u64)>>,
+        voting_settings: VotingSettings<T::BlockNumber>,
+        result: CountResult,
+        ballots: &BallotsView<T>,
     );
 }
 
-// basic implementations
-
-macro_rules! finalize_voting_dispatch_trait_impls {
-    ($($name:ident)*) => {
-        impl<T: Config, $($name: FinalizeVotingDispatchTrait<T>,)*> FinalizeVotingDispatchTrait<T> for ($($name,)*) {
-            fn finalize_voting(_subject: T::Hash, _voting_setting: VotingSettings<T::BlockNumber>) {
-                $($name::finalize_voting(_subject.clone(), _voting_setting.clone());)*
-            }
-        }
-    };
+// the ballots of the finalized voting, they are read from the storage only when a finalizer
+// iterates them and are removed in the next blocks after all finalizers are done
+pub struct BallotsView<T: Config> {
+    subject: T::Hash,
+    submitted_height: T::BlockNumber,
 }
 
-finalize_voting_dispatch_trait_impls! {}
-finalize_voting_dispatch_trait_impls! {_1}
-finalize_voting_dispatch_trait_impls! {_1 _2}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7 _8}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7 _8 _9}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7 _8 _9 _10}
-
-macro_rules! finalize_voting_dispatch_trait_impls {
-    ($($name:ident)*) => {
-        impl<T: Config, $($name: FinilizeAltVotingDispatchTrait<T>,)*> FinilizeAltVotingDispatchTrait<T> for ($($name,)*) {
-            fn finalize_voting(_subject: T::Hash, _voting_setting: AltVoutingSettings<T::BlockNumber>,_winner: Candidate) {
-                $($name::finalize_voting(_subject.clone(), _voting_setting.clone(),_winner.clone());)*
-            }
+impl<T: Config> BallotsView<T> {
+    pub fn new(subject: T::Hash, submitted_height: T::BlockNumber) -> Self {
+        Self {
+            subject,
+            submitted_height,
         }
-    };
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (T::AccountId, (AltVote, u64))> {
+        Pallet::<T>::subject_ballots(self.subject, self.submitted_height)
+    }
 }
 
-finalize_voting_dispatch_trait_impls! {}
-finalize_voting_dispatch_trait_impls! {_1 }
-finalize_voting_dispatch_trait_impls! {_1 _2}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7 _8}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7 _8 _9}
-finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4 _5 _6 _7 _8 _9 _10}
+// basic implementations
 
 macro_rules! finalize_voting_dispatch_trait_impls {
     ($($name:ident)*) => {
-        impl<T: Config, $($name: FinalizeAltVotingListDispatchTrait<T>,)*> FinalizeAltVotingListDispatchTrait<T> for ($($name,)*) {
-            fn finalize_voting(_subject: T::Hash, _voting_setting: AltVotingListSettings<T::BlockNumber>,_winners: BTreeMap<Candidate, u64>,_ballots_storage: BTreeMap<T::Hash, BTreeMap<T::AccountId, (AltVote, u64)>>) {
-                $($name::finalize_voting(_subject.clone(), _voting_setting.clone(),_winners.clone(),_ballots_storage.clone());)*
+        impl<T: Config, $($name: FinalizeVotingDispatchTrait<T>,)*> FinalizeVotingDispatchTrait<T> for ($($name,)*) {
+            fn finalize_voting(_subject: T::Hash, _voting_settings: VotingSettings<T::BlockNumber>, _result: CountResult, _ballots: &BallotsView<T>) {
+                $($name::finalize_voting(_subject.clone(), _voting_settings.clone(), _result.clone(), _ballots);)*
             }
         }
     };
 }
 
 finalize_voting_dispatch_trait_impls! {}
-finalize_voting_dispatch_trait_impls! {_1 }
+finalize_voting_dispatch_trait_impls! {_1}
 finalize_voting_dispatch_trait_impls! {_1 _2}
 finalize_voting_dispatch_trait_impls! {_1 _2 _3}
 finalize_voting_dispatch_trait_impls! {_1 _2 _3 _4}
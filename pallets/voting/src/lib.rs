@@ -1,8 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::codec::{Decode, Encode};
+use frame_support::sp_runtime::{PerThing, Percent};
 use frame_system::pallet_prelude::BlockNumberFor;
-use if_chain::if_chain;
 pub use pallet::*;
 use sp_std::{
     cmp::{Ord, PartialOrd},
@@ -18,43 +18,159 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod counting;
+pub use counting::{CountAction, CountResult, CountRound, TieBreakSeed};
 pub mod finalize_voiting_trait;
-use crate::finalize_voiting_trait::FinalizeAltVotingListDispatchTrait; // FinalizeAltVotingListDispatchTrait
-pub use finalize_voiting_trait::FinalizeVotingDispatchTrait;
-pub use finalize_voiting_trait::FinilizeAltVotingDispatchTrait;
+pub mod voting_method;
+pub use finalize_voiting_trait::{BallotsView, FinalizeVotingDispatchTrait};
+pub use voting_method::{CountingMethod, VotingMethod};
 
 #[frame_support::pallet]
 pub mod pallet {
 
     use super::*;
     use frame_support::pallet_prelude::*;
+    use frame_support::sp_runtime::traits::{Hash, Saturating};
+    use frame_support::storage::bounded_vec::BoundedVec;
+    use frame_support::weights::{DispatchClass, Weight};
+    use sp_std::convert::TryFrom;
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
         type FinalizeVotingDispatch: FinalizeVotingDispatchTrait<Self>;
-        type FinalizeAltVotingDispatch: FinilizeAltVotingDispatchTrait<Self>;
-        type FinalizeAltVotingListDispatch: FinalizeAltVotingListDispatchTrait<Self>;
+
+        // the most preferences a ballot can rank
+        #[pallet::constant]
+        type MaxPreferences: Get<u32>;
+
+        // the longest candidate id a ballot can name
+        #[pallet::constant]
+        type MaxCandidateLength: Get<u32>;
+
+        // the most votings finalized in a block, the rest is deferred to the next blocks
+        #[pallet::constant]
+        type MaxFinalizationsPerBlock: Get<u32>;
+
+        // the most ballots and commitments of the finalized votings removed in a block
+        #[pallet::constant]
+        type MaxBallotRemovalsPerBlock: Get<u32>;
+
+        // the most voters a delegate represents directly and through the other delegates,
+        // it bounds the weight of the votes cast for the delegators
+        #[pallet::constant]
+        type MaxDelegators: Get<u32>;
     }
 
+    pub type BoundedCandidate<T> = BoundedVec<u8, <T as Config>::MaxCandidateLength>;
+
+    // `AltVote` as it is kept in the storage, the preferences and the scores
+    pub type BoundedAltVote<T> = (
+        BoundedVec<BoundedCandidate<T>, <T as Config>::MaxPreferences>,
+        BoundedVec<u32, <T as Config>::MaxPreferences>,
+    );
+
     #[pallet::pallet]
     #[pallet::generate_store(trait Store)]
     pub struct Pallet<T>(_);
 
+    #[pallet::event]
+    #[pallet::metadata(
+        T::Hash = "Hash",
+        T::BlockNumber = "BlockNumber",
+        T::AccountId = "AccountId"
+    )]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        // a voting on the subject is created, it ends in the block
+        VotingCreated(T::Hash, T::BlockNumber),
+        // a vote is cast or changed in the voting on the subject
+        VoteCast(T::Hash),
+        // a motion is finalized with the summed power of the votes and whether the quorum
+        // was reached
+        VotingFinalized(T::Hash, u64, bool),
+        // a voting with candidates is finalized with the winners
+        AltVotingFinalized(T::Hash, Vec<Candidate>),
+        // the voter delegated the vote on the topic to the delegate
+        Delegated(DelegationTopic, T::AccountId, T::AccountId),
+        // the voter withdrew the delegation of the vote on the topic
+        Undelegated(DelegationTopic, T::AccountId),
+    }
+
     #[pallet::error]
     pub enum Error<T> {
         // emits when from provided VotingSubject has been created
         VotingHasBeenCreated,
+        // emits when a voting with the subject was created and finalized in this block,
+        // its ballots are not removed yet
+        BallotsAreBeingRemoved,
+        // emits when the blocks from the end of the voting have no room for its finalization
+        ScheduleIsFull,
         // emits when provided Voting subject does not exist
         VotingSubjectDoesNotExist,
+        // emits when a winner can not be determined because no ballot names a candidate
+        NoValidBallots,
+        // emits when a vote is cast openly in a voting with the secret ballot
+        SecretVoting,
+        // emits when a commitment is submitted to a voting with the open ballot
+        NotSecretVoting,
+        // emits when a commitment is submitted after the end of the voting period
+        CommitPhaseIsOver,
+        // emits when a ballot is revealed outside of the reveal window
+        NotRevealPhase,
+        // emits when the voter has no commitment to reveal
+        CommitmentNotFound,
+        // emits when the revealed ballot and salt do not match the commitment
+        CommitmentMismatch,
+        // emits when a ballot ranks more than `MaxPreferences` candidates
+        TooManyPreferences,
+        // emits when a ballot names a candidate longer than `MaxCandidateLength`
+        CandidateTooLong,
+        // emits when a ballot names a candidate that is not registered in the voting
+        UnknownCandidate,
+        // emits when a ballot names the same candidate more than once
+        DuplicateCandidate,
+        // emits when a vote is cast after the end of the voting period
+        VotingPeriodIsOver,
+        // emits when all of the known voters of the voting have already voted
+        AllVotersVoted,
+        // emits when a voter delegates the vote to themselves
+        SelfDelegation,
+        // emits when the delegation would lead back to the voter through the delegates
+        DelegationCycle,
+        // emits when the voter has no delegation on the topic to withdraw
+        DelegationNotFound,
+        // emits when a delegate in the chain would represent more than `MaxDelegators` voters
+        TooManyDelegators,
     }
 
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        // a page of the ballots of the finalized votings is removed, the weight of the votings
+        // finalized in this block is added, the votings closed early by the votes of this block
+        // add their weight when they are closed, every finalized block of the schedule is taken
+        // and the next one is written
+        fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+            let blocks = Self::scheduled_blocks(block_number).0.len() as Weight;
+            let writes = if blocks > 0 {
+                blocks.saturating_add(1)
+            } else {
+                0
+            };
+            let weight = T::DbWeight::get()
+                .reads_writes(blocks.saturating_add(2), writes)
+                .saturating_add(Self::remove_stale_ballots());
+            Self::due_votings(block_number)
+                .into_iter()
+                .fold(weight, |weight, (_, finalization)| {
+                    weight.saturating_add(finalization)
+                })
+        }
+
         // Block finalization
         fn on_finalize(block_number: BlockNumberFor<T>) {
             Self::finalize_votings(block_number);
-            Self::finalize_alt_votings(block_number);
-            Self::finalize_list_alt_votings(block_number);
         }
     }
 
@@ -63,336 +179,671 @@ pub mod pallet {
     type ActiveVotings<T: Config> =
         StorageMap<_, Blake2_128Concat, T::Hash, VotingSettings<T::BlockNumber>, OptionQuery>;
 
+    // results of the finalized votings with candidates by the subject and the block they were
+    // finalized in, the subjects of the periodic elections are reused for every term
     #[pallet::storage]
-    #[pallet::getter(fn active_alt_votings)]
-    type ActiveAltVoitings<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::Hash, AltVoutingSettings<T::BlockNumber>, OptionQuery>;
-
-    #[pallet::storage]
-    #[pallet::getter(fn active_alt_list_votings)]
-    type ActiveAltListVoitings<T: Config> = StorageMap<
+    #[pallet::getter(fn election_results)]
+    type ElectionResults<T: Config> = StorageDoubleMap<
         _,
         Blake2_128Concat,
         T::Hash,
-        AltVotingListSettings<T::BlockNumber>,
+        Blake2_128Concat,
+        T::BlockNumber,
+        ElectionResult,
         OptionQuery,
     >;
+
+    // ballots of the voters with their power by the subject with the block the voting was
+    // created in and the voter, the subjects are reused by the later votings
     #[pallet::storage]
-    type BallotsStorage<T: Config> = StorageMap<
+    type BallotsStorage<T: Config> = StorageDoubleMap<
         _,
         Blake2_128Concat,
-        T::Hash,
-        BTreeMap<T::AccountId, (AltVote, u64)>,
+        (T::Hash, T::BlockNumber),
+        Blake2_128Concat,
+        T::AccountId,
+        (BoundedAltVote<T>, u64),
+        OptionQuery,
+    >;
+
+    // the active votings by the block they are finalized in, a voting goes to the first block
+    // from its end which has room for it
+    #[pallet::storage]
+    type VotingsSchedule<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<T::Hash, T::MaxFinalizationsPerBlock>,
         ValueQuery,
-        DefaultBallot<T>,
     >;
 
-    #[pallet::type_value]
-    pub fn DefaultBallot<T: Config>() -> BTreeMap<T::AccountId, (AltVote, u64)> {
-        Default::default()
-    }
+    // the first block of `VotingsSchedule` which is not finalized yet
+    #[pallet::storage]
+    type NextScheduledBlock<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+    // the block of `VotingsSchedule` the active voting is in
+    #[pallet::storage]
+    type ScheduledBlocks<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::Hash, T::BlockNumber, OptionQuery>;
+
+    // the ended votings by the position in the queue, they are the votings closed early and
+    // the votings which did not fit into the finalizations of their block, they are finalized
+    // first
+    #[pallet::storage]
+    type DeferredVotings<T: Config> = StorageMap<_, Twox64Concat, u32, T::Hash, OptionQuery>;
+
+    // the first and the next free position of `DeferredVotings`
+    #[pallet::storage]
+    type DeferredRange<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
+
+    // the number of the voters who cast a ballot or a commitment by the subject
+    #[pallet::storage]
+    type BallotsNumber<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, u32, ValueQuery>;
+
+    // commitments of the secret votings with the power of the voters by the same keys as
+    // `BallotsStorage`, they are moved to `BallotsStorage` once revealed
+    #[pallet::storage]
+    type Commitments<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        (T::Hash, T::BlockNumber),
+        Blake2_128Concat,
+        T::AccountId,
+        (T::Hash, u64),
+        OptionQuery,
+    >;
+
+    // the finalized votings by the subject and the block they were created in, their ballots
+    // and commitments are removed a page in a block
+    #[pallet::storage]
+    type StaleBallots<T: Config> =
+        StorageMap<_, Blake2_128Concat, (T::Hash, T::BlockNumber), (), OptionQuery>;
+
+    // the delegate of the voter by the topic
+    #[pallet::storage]
+    #[pallet::getter(fn delegations)]
+    type Delegations<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        DelegationTopic,
+        Blake2_128Concat,
+        T::AccountId,
+        T::AccountId,
+        OptionQuery,
+    >;
+
+    // the voters who delegated to the delegate by the topic and the delegate,
+    // the reverse of `Delegations`
+    #[pallet::storage]
+    type Delegators<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        (DelegationTopic, T::AccountId),
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    // the number of the voters the delegate represents directly and through the other
+    // delegates by the topic, at most `MaxDelegators`
+    #[pallet::storage]
+    #[pallet::getter(fn represented)]
+    type Represented<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        DelegationTopic,
+        Blake2_128Concat,
+        T::AccountId,
+        u32,
+        ValueQuery,
+    >;
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {}
 
     impl<T: Config> Pallet<T> {
+        // the deferred and the early closed votings are finalized first, the scheduled votings
+        // which do not fit into the finalizations of the block are deferred
         fn finalize_votings(block_number: BlockNumberFor<T>) {
-            for (subject, voting_settings) in <ActiveVotings<T>>::iter() {
-                if voting_settings.voters_number == voting_settings.voted {
-                    <T::FinalizeVotingDispatch>::finalize_voting(subject, voting_settings.clone());
-                    <ActiveVotings<T>>::remove(subject);
+            let mut planned = (0, 0);
+            let (mut head, tail) = <DeferredRange<T>>::get();
+            while head != tail {
+                if let Some(subject) = <DeferredVotings<T>>::get(head) {
+                    let weight = Self::subject_finalization_weight(subject);
+                    if !Self::fits_block(&mut planned, weight) {
+                        break;
+                    }
+                    <DeferredVotings<T>>::remove(head);
+                    Self::finalize_scheduled(subject, block_number);
                 }
-                // voting has been passed, so we will store the result and remove from the active votings list
-                if (voting_settings.voting_duration + voting_settings.submitted_height)
-                    <= block_number
-                {
-                    <T::FinalizeVotingDispatch>::finalize_voting(subject, voting_settings);
-                    <ActiveVotings<T>>::remove(subject);
+                head = head.wrapping_add(1);
+            }
+            <DeferredRange<T>>::mutate(|range| range.0 = head);
+            let (blocks, next) = Self::scheduled_blocks(block_number);
+            if blocks.is_empty() {
+                return Self::reset_deferred();
+            }
+            // the votings scheduled by the finalizers rewind it again
+            <NextScheduledBlock<T>>::put(next);
+            for block in blocks.into_iter() {
+                for subject in <VotingsSchedule<T>>::take(block).into_iter() {
+                    let weight = Self::subject_finalization_weight(subject);
+                    if Self::fits_block(&mut planned, weight) {
+                        Self::finalize_scheduled(subject, block_number);
+                    } else {
+                        Self::defer(subject);
+                    }
                 }
             }
+            Self::reset_deferred();
         }
-        fn finalize_alt_votings(block_number: BlockNumberFor<T>) {
-            <ActiveAltVoitings<T>>::iter().for_each(|(subject, alt_voting_settings)| {
-                if alt_voting_settings.voters_number == alt_voting_settings.voted {
-                    if let Ok(winner) = Self::calculate_alt_vote_winner(subject) {
-                        <T::FinalizeAltVotingDispatch>::finalize_voting(
-                            subject,
-                            alt_voting_settings.clone(),
-                            winner,
-                        );
-                    }
-                    <BallotsStorage<T>>::remove(subject);
-                    <ActiveAltVoitings<T>>::remove(subject);
+
+        // takes a voting with the weight into the finalizations of the block if there is room
+        // for it in `MaxFinalizationsPerBlock` and the budget, the first voting always fits,
+        // once a voting does not fit the block is closed to keep the order of the rest
+        fn fits_block(planned: &mut (u32, Weight), weight: Weight) -> bool {
+            let (number, total) = *planned;
+            let total = total.saturating_add(weight);
+            let fits = number == 0
+                || (number < T::MaxFinalizationsPerBlock::get()
+                    && total <= Self::finalizations_budget());
+            *planned = if fits {
+                (number.saturating_add(1), total)
+            } else {
+                (u32::MAX, total)
+            };
+            fits
+        }
+
+        fn finalize_scheduled(subject: T::Hash, block_number: T::BlockNumber) {
+            <ScheduledBlocks<T>>::remove(subject);
+            if let Some(voting_settings) = <ActiveVotings<T>>::take(subject) {
+                Self::finalize_voting(subject, voting_settings, block_number);
+            }
+        }
+
+        // the voting is finalized in the first block from its end which has room for it,
+        // at most `MaxFinalizationsPerBlock` blocks are probed
+        fn schedule(subject: T::Hash, end: T::BlockNumber) -> Result<(), Error<T>> {
+            let mut block = end;
+            let mut probes = T::MaxFinalizationsPerBlock::get();
+            while <VotingsSchedule<T>>::try_mutate(block, |scheduled| scheduled.try_push(subject))
+                .is_err()
+            {
+                probes = probes.saturating_sub(1);
+                ensure!(probes > 0, <Error<T>>::ScheduleIsFull);
+                block = block.saturating_add(1u32.into());
+            }
+            <ScheduledBlocks<T>>::insert(subject, block);
+            <NextScheduledBlock<T>>::mutate(|next| {
+                if next.map_or(true, |next| block < next) {
+                    *next = Some(block);
                 }
-                if (alt_voting_settings.voting_duration + alt_voting_settings.submitted_height)
-                    <= block_number
-                {
-                    if let Ok(winner) = Self::calculate_alt_vote_winner(subject) {
-                        <T::FinalizeAltVotingDispatch>::finalize_voting(
-                            subject,
-                            alt_voting_settings,
-                            winner,
-                        );
-                    }
+            });
+            Ok(())
+        }
 
-                    <BallotsStorage<T>>::remove(subject);
-                    <ActiveAltVoitings<T>>::remove(subject);
+        fn unschedule(subject: T::Hash) {
+            if let Some(block) = <ScheduledBlocks<T>>::take(subject) {
+                let mut scheduled = <VotingsSchedule<T>>::get(block);
+                scheduled.retain(|scheduled| *scheduled != subject);
+                if scheduled.is_empty() {
+                    <VotingsSchedule<T>>::remove(block);
+                } else {
+                    <VotingsSchedule<T>>::insert(block, scheduled);
                 }
+            }
+        }
+
+        fn defer(subject: T::Hash) {
+            <DeferredRange<T>>::mutate(|(_, tail)| {
+                <DeferredVotings<T>>::insert(*tail, subject);
+                *tail = tail.wrapping_add(1);
             });
         }
-        #[allow(clippy::type_complexity)]
-        fn finalize_list_alt_votings(block_number: BlockNumberFor<T>) {
-            <ActiveAltListVoitings<T>>::iter().for_each(|(subject, alt_voting_settings)| {
-                if (alt_voting_settings.voting_duration + alt_voting_settings.submitted_height)
-                    <= block_number
-                {
-                    let winners = Self::calculate_alt_vote_winners_list(subject).unwrap();
-                    let ballots_storage: BTreeMap<T::Hash, BTreeMap<T::AccountId, (AltVote, u64)>> =
-                        <BallotsStorage<T>>::iter().collect();
-                    <T::FinalizeAltVotingListDispatch>::finalize_voting(
-                        subject,
-                        alt_voting_settings,
-                        winners,
-                        ballots_storage,
-                    );
-                    <BallotsStorage<T>>::remove(subject);
-                    <ActiveAltListVoitings<T>>::remove(subject);
+
+        fn reset_deferred() {
+            <DeferredRange<T>>::mutate(|range| {
+                if range.0 == range.1 {
+                    *range = (0, 0);
                 }
             });
         }
-        pub fn calculate_alt_vote_winner(subject: T::Hash) -> Result<Candidate, Error<T>> {
-            if let Some(settings) = <ActiveAltVoitings<T>>::get(subject) {
-                let ballots_list = <BallotsStorage<T>>::get(subject);
-                let mut candidate_list: BTreeMap<Candidate, u64> = settings
-                    .candidates
-                    .iter()
-                    .map(|candidate| (candidate.clone(), 0))
-                    .collect();
-
-                ballots_list.iter().for_each(|(_, ballot)| {
-                    if_chain! {
-                        if let Some(vout) = ballot.0.content.front();
-                        if let Some(result) = candidate_list.get_mut(vout);
-                        then {
-                            *result += ballot.1;
-                        }
-                    }
-                });
 
-                let all_voutes: u64 = candidate_list.iter().map(|(_, result)| *result).sum();
+        fn deferred_number() -> u32 {
+            let (head, tail) = <DeferredRange<T>>::get();
+            tail.wrapping_sub(head)
+        }
 
-                if let Some((max_vouts_candidate, result)) =
-                    candidate_list.iter().max_by_key(|(_, result)| *result)
-                {
-                    if ((*result as f64 / all_voutes as f64) * 100.0) > 50.0 {
-                        return Ok(max_vouts_candidate.clone());
+        // the blocks of `VotingsSchedule` finalized in the block with the first block left to
+        // the next blocks, it is only this block unless some blocks were skipped, the skipped
+        // blocks are caught up by `MaxFinalizationsPerBlock` blocks at a time
+        fn scheduled_blocks(block_number: T::BlockNumber) -> (Vec<T::BlockNumber>, T::BlockNumber) {
+            let mut blocks = Vec::new();
+            let mut next = block_number.saturating_add(1u32.into());
+            if let Some(mut block) = <NextScheduledBlock<T>>::get() {
+                while block < block_number {
+                    if blocks.len() as u32 >= T::MaxFinalizationsPerBlock::get() {
+                        next = block;
+                        break;
                     }
+                    blocks.push(block);
+                    block = block.saturating_add(1u32.into());
                 }
-
-                while candidate_list.len() >= 2 {
-                    let mut removeble_key = Vec::new();
-                    if let Some((min_voted_candidate, _)) =
-                        candidate_list.iter_mut().min_by_key(|(_, result)| **result)
-                    {
-                        removeble_key = min_voted_candidate.clone();
-                        let buffer: Vec<_> = ballots_list
-                            .iter()
-                            .filter(|(_, ballot)| {
-                                ballot.0.content.front() == Some(min_voted_candidate)
-                            })
-                            .collect();
-                        buffer.iter().for_each(|(_, ballot)| {
-                            let mut ballot_tmp = ballot.0.content.clone();
-                            ballot_tmp.pop_front();
-                            if_chain! {
-                                if let Some(vout) = ballot_tmp.front();
-                                if *vout != removeble_key;
-                                if let Some(result) = candidate_list.get_mut(vout);
-                                then {
-                                    *result += ballot.1;
-                                }
-                            }
-                        });
-                    }
-                    candidate_list.remove(&removeble_key);
-                    let all_voutes: u64 = candidate_list.iter().map(|(_, result)| *result).sum();
-                    if let Some((max_vouts_candidate, result)) =
-                        candidate_list.iter().max_by_key(|(_, result)| *result)
-                    {
-                        if ((*result as f64 / all_voutes as f64) * 100.0) > 50.0 {
-                            return Ok(max_vouts_candidate.clone());
-                        }
-                    }
+                if block <= block_number {
+                    blocks.push(block_number);
                 }
             }
-            Err(<Error<T>>::VotingSubjectDoesNotExist)
+            (blocks, next)
         }
 
-        pub fn calculate_alt_vote_winners_list(
+        // voting has been passed, so we will store the result and remove it from the active
+        // votings before the finalizers are dispatched, they can create a new voting with the subject
+        fn finalize_voting(
             subject: T::Hash,
-        ) -> Result<BTreeMap<Candidate, u64>, Error<T>> {
-            if let Some(settings) = <ActiveAltListVoitings<T>>::get(subject) {
-                let ballots_list = <BallotsStorage<T>>::get(subject);
-                let mut candidate_list: BTreeMap<Candidate, u64> = settings
-                    .candidates
-                    .iter()
-                    .map(|candidate| (candidate.clone(), 0))
-                    .collect();
-
-                ballots_list.iter().for_each(|(_, ballot)| {
-                    if_chain! {
-                        if let Some(vout) = ballot.0.content.front();
-                        if let Some(result) = candidate_list.get_mut(vout);
-                        then {
-                            *result += ballot.1;
-                        }
-                    }
-                });
+            voting_settings: VotingSettings<T::BlockNumber>,
+            block_number: T::BlockNumber,
+        ) {
+            // unrevealed ballots are excluded, the ballots and the commitments are removed
+            // in the next blocks
+            if <BallotsNumber<T>>::take(subject) > 0 {
+                <StaleBallots<T>>::insert((subject, voting_settings.submitted_height), ());
+            }
+            let ballots = BallotsView::<T>::new(subject, voting_settings.submitted_height);
+            // nobody is elected by a voting without the quorum
+            let result = if voting_settings.quorum_reached() {
+                Self::count(&voting_settings, &ballots)
+            } else {
+                CountResult::default()
+            };
+            if voting_settings.candidates.is_empty() {
+                Self::deposit_event(Event::VotingFinalized(
+                    subject,
+                    voting_settings.result,
+                    voting_settings.quorum_reached(),
+                ));
+            } else {
+                Self::deposit_event(Event::AltVotingFinalized(
+                    subject,
+                    result.winners.keys().cloned().collect(),
+                ));
+                <ElectionResults<T>>::insert(
+                    subject,
+                    block_number,
+                    ElectionResult {
+                        method: voting_settings.method,
+                        candidates: voting_settings.candidates.clone(),
+                        winners_amount: voting_settings.winners_amount,
+                        count: result.clone(),
+                    },
+                );
+            }
+            <T::FinalizeVotingDispatch>::finalize_voting(
+                subject,
+                voting_settings,
+                result,
+                &ballots,
+            );
+        }
 
-                while candidate_list.len() > settings.winners_amount as usize {
-                    let mut removeble_key = Vec::new();
-                    if let Some((min_voted_candidate, _)) =
-                        candidate_list.iter_mut().min_by_key(|(_, result)| **result)
-                    {
-                        removeble_key = min_voted_candidate.clone();
-                        let buffer: Vec<_> = ballots_list
-                            .iter()
-                            .filter(|(_, ballot)| {
-                                ballot.0.content.front() == Some(min_voted_candidate)
-                            })
-                            .collect();
-                        buffer.iter().for_each(|(_, ballot)| {
-                            let mut ballot_tmp = ballot.0.content.clone();
-                            ballot_tmp.pop_front();
-                            if_chain! {
-                                if let Some(vout) = ballot_tmp.front();
-                                if *vout != removeble_key;
-                                if let Some(result) = candidate_list.get_mut(vout);
-                                then {
-                                    *result += ballot.1;
-                                }
-                            }
-                        });
-                    }
-                    candidate_list.remove(&removeble_key);
-                }
-                return Ok(candidate_list);
+        // removes a page of the ballots and the commitments of a finalized voting, the voting
+        // is forgotten once nothing is left of it, returns the weight of the removal
+        fn remove_stale_ballots() -> Weight {
+            let key = match <StaleBallots<T>>::iter().next() {
+                Some((key, ())) => key,
+                None => return T::DbWeight::get().reads(1),
+            };
+            let limit = T::MaxBallotRemovalsPerBlock::get() as usize;
+            let ballots: Vec<_> = <BallotsStorage<T>>::iter_prefix(key)
+                .map(|(voter, _)| voter)
+                .take(limit)
+                .collect();
+            for voter in ballots.iter() {
+                <BallotsStorage<T>>::remove(key, voter);
+            }
+            let commitments: Vec<_> = <Commitments<T>>::iter_prefix(key)
+                .map(|(voter, _)| voter)
+                .take(limit.saturating_sub(ballots.len()))
+                .collect();
+            for voter in commitments.iter() {
+                <Commitments<T>>::remove(key, voter);
             }
+            let removed = ballots.len().saturating_add(commitments.len());
+            if removed < limit {
+                <StaleBallots<T>>::remove(key);
+            }
+            let removed = removed as Weight;
+            T::DbWeight::get().reads_writes(removed.saturating_add(3), removed.saturating_add(1))
+        }
 
-            Err(<Error<T>>::VotingSubjectDoesNotExist)
+        // the most weight of removing a page of the ballots
+        pub fn ballot_removal_weight() -> Weight {
+            let limit = T::MaxBallotRemovalsPerBlock::get() as Weight;
+            T::DbWeight::get().reads_writes(limit.saturating_add(3), limit.saturating_add(1))
         }
-    }
 
-    impl<T: Config> VotingTrait<T> for Pallet<T> {
-        fn create_voting(
-            subject: T::Hash,
-            duration: T::BlockNumber,
-            all_voters: Option<u32>,
+        // the delegates the vote passes through starting with `next`, the chain ends
+        // as the delegations are acyclic
+        fn delegation_chain(
+            topic: DelegationTopic,
+            mut next: Option<T::AccountId>,
+        ) -> Vec<T::AccountId> {
+            let mut chain = Vec::new();
+            while let Some(account) = next {
+                next = <Delegations<T>>::get(topic, &account);
+                chain.push(account);
+            }
+            chain
+        }
+
+        // the votings finalized in the block with their weight, the deferred ones go first and
+        // then the scheduled up to the block as long as they fit into the block
+        fn due_votings(block_number: T::BlockNumber) -> Vec<(T::Hash, Weight)> {
+            let (head, _) = <DeferredRange<T>>::get();
+            let deferred = (0..Self::deferred_number())
+                .filter_map(move |position| <DeferredVotings<T>>::get(head.wrapping_add(position)));
+            let scheduled = Self::scheduled_blocks(block_number)
+                .0
+                .into_iter()
+                .flat_map(|block| <VotingsSchedule<T>>::get(block).into_iter());
+            let mut planned = (0, 0);
+            deferred
+                .chain(scheduled)
+                .map(|subject| (subject, Self::subject_finalization_weight(subject)))
+                .take_while(|(_, weight)| Self::fits_block(&mut planned, *weight))
+                .collect()
+        }
+
+        // the weight the finalizations of a block take, the other half is left to the
+        // extrinsics and the other hooks
+        pub fn finalizations_budget() -> Weight {
+            T::BlockWeights::get().max_block / 2
+        }
+
+        // the weight of finalizing the voting with the ballots cast so far
+        fn subject_finalization_weight(subject: T::Hash) -> Weight {
+            Self::finalization_weight(<BallotsNumber<T>>::get(subject))
+        }
+
+        // the weight of finalizing a voting with the number of ballots, the ballots are read
+        // to count them and once more by the finalizers, they are removed later by pages,
+        // the own work of the finalizers is not included
+        pub fn finalization_weight(ballots: u32) -> Weight {
+            let ballots = ballots as Weight;
+            T::DbWeight::get().reads_writes(4u64.saturating_add(ballots.saturating_mul(2)), 7)
+        }
+
+        // the most weight of creating a voting, the schedule is probed for the block of the
+        // finalization at most `MaxFinalizationsPerBlock` times
+        pub fn creation_weight() -> Weight {
+            let probes = T::MaxFinalizationsPerBlock::get() as Weight;
+            T::DbWeight::get().reads_writes(4u64.saturating_add(probes), 4)
+        }
+
+        // the deferred votings do not take the votes after their end
+        fn ensure_voting_period(
+            voting_settings: &VotingSettings<T::BlockNumber>,
         ) -> Result<(), Error<T>> {
+            let block_number = <frame_system::Pallet<T>>::block_number();
             ensure!(
-                <ActiveVotings<T>>::get(subject) == None,
-                <Error<T>>::VotingHasBeenCreated
+                block_number <= voting_settings.submitted_height + voting_settings.voting_duration,
+                <Error<T>>::VotingPeriodIsOver
             );
+            Ok(())
+        }
 
-            let block_number = <frame_system::Pallet<T>>::block_number();
-            if let Some(all) = all_voters {
-                <ActiveVotings<T>>::insert(
-                    subject,
-                    VotingSettings {
-                        result: 0,
-                        voting_duration: duration,
-                        submitted_height: block_number,
-                        voted: 0,
-                        voters_number: all,
-                    },
+        // a voting with the known voters takes no more votes than the number of them
+        fn ensure_not_all_voted(
+            voting_settings: &VotingSettings<T::BlockNumber>,
+        ) -> Result<(), Error<T>> {
+            ensure!(!voting_settings.all_voted(), <Error<T>>::AllVotersVoted);
+            Ok(())
+        }
+
+        // a voting with the early close ends as soon as all of the known voters voted, it is
+        // finalized in this block if there is room, the weight was not known to `on_initialize`
+        fn close_if_all_voted(subject: T::Hash, voting_settings: &VotingSettings<T::BlockNumber>) {
+            if voting_settings.rules.early_close && voting_settings.all_voted() {
+                Self::unschedule(subject);
+                Self::defer(subject);
+                let now = <frame_system::Pallet<T>>::block_number();
+                let due = Self::due_votings(now);
+                if let Some((_, weight)) = due.iter().find(|(due, _)| *due == subject) {
+                    <frame_system::Pallet<T>>::register_extra_weight_unchecked(
+                        *weight,
+                        DispatchClass::Mandatory,
+                    );
+                }
+            }
+        }
+
+        // the last block of the voting, including the reveal window of a secret voting
+        fn voting_end(voting_settings: &VotingSettings<T::BlockNumber>) -> T::BlockNumber {
+            voting_settings.submitted_height
+                + voting_settings.voting_duration
+                + voting_settings.reveal_duration.unwrap_or_default()
+        }
+
+        // the commitment a voter has to submit for the ballot in a secret voting, the salt
+        // keeps the ballot from being guessed from the commitment, the voter and the subject
+        // keep the commitment from being copied by another voter or into another voting
+        pub fn commitment_of(
+            account_id: &T::AccountId,
+            subject: T::Hash,
+            ballot: &AltVote,
+            salt: &[u8],
+        ) -> T::Hash {
+            T::Hashing::hash_of(&(account_id, subject, ballot, salt))
+        }
+
+        // checks that the ballot fits the limits and names only the registered candidates once
+        fn bound(
+            voting_settings: &VotingSettings<T::BlockNumber>,
+            ballot: AltVote,
+        ) -> Result<BoundedAltVote<T>, Error<T>> {
+            let mut named = BTreeSet::new();
+            let mut content = Vec::with_capacity(ballot.content.len());
+            for candidate in ballot.content.into_iter() {
+                ensure!(
+                    voting_settings.candidates.contains(&candidate),
+                    <Error<T>>::UnknownCandidate
                 );
-            } else {
-                <ActiveVotings<T>>::insert(
-                    subject,
-                    VotingSettings {
-                        result: 0,
-                        voting_duration: duration,
-                        submitted_height: block_number,
-                        voted: 0,
-                        voters_number: 0,
-                    },
+                ensure!(
+                    named.insert(candidate.clone()),
+                    <Error<T>>::DuplicateCandidate
+                );
+                content.push(
+                    BoundedCandidate::<T>::try_from(candidate)
+                        .map_err(|_| <Error<T>>::CandidateTooLong)?,
                 );
             }
+            let content =
+                BoundedVec::try_from(content).map_err(|_| <Error<T>>::TooManyPreferences)?;
+            let scores =
+                BoundedVec::try_from(ballot.scores).map_err(|_| <Error<T>>::TooManyPreferences)?;
+            Ok((content, scores))
+        }
 
-            Ok(())
+        fn unbound((content, scores): BoundedAltVote<T>) -> AltVote {
+            AltVote::with_scores(
+                content.iter().map(|candidate| candidate.to_vec()).collect(),
+                scores.to_vec(),
+            )
         }
 
-        fn create_alt_voting(
+        // counts a new voter of the subject, the number of the ballots and the commitments
+        // weighs the finalization of the voting
+        fn count_voter(subject: T::Hash) {
+            <BallotsNumber<T>>::mutate(subject, |ballots_number| {
+                *ballots_number = ballots_number.saturating_add(1)
+            });
+        }
+
+        // the ballots of the voting with the subject created in the block
+        // with the power of the voters
+        pub fn subject_ballots(
             subject: T::Hash,
-            duration: T::BlockNumber,
-            candidates: BTreeSet<Candidate>,
-            all_voters: Option<u32>,
-        ) -> Result<(), Error<T>> {
-            ensure!(
-                <ActiveAltVoitings<T>>::get(subject) == None,
-                <Error<T>>::VotingHasBeenCreated
-            );
+            submitted_height: T::BlockNumber,
+        ) -> impl Iterator<Item = (T::AccountId, (AltVote, u64))> {
+            <BallotsStorage<T>>::iter_prefix((subject, submitted_height))
+                .map(|(voter, (ballot, power))| (voter, (Self::unbound(ballot), power)))
+        }
 
-            let block_number = <frame_system::Pallet<T>>::block_number();
-            if let Some(all) = all_voters {
-                <ActiveAltVoitings<T>>::insert(
-                    subject,
-                    AltVoutingSettings {
-                        voting_duration: duration,
-                        submitted_height: block_number,
-                        candidates,
-                        voted: 0,
-                        voters_number: all,
-                    },
-                );
+        fn count(
+            voting_settings: &VotingSettings<T::BlockNumber>,
+            ballots: &BallotsView<T>,
+        ) -> CountResult {
+            let ballots: Vec<(AltVote, u64)> = ballots.iter().map(|(_, ballot)| ballot).collect();
+            voting_settings.method.count(
+                &voting_settings.candidates,
+                voting_settings.winners_amount,
+                &ballots,
+                &voting_settings.tie_break_seed,
+            )
+        }
+
+        // counts the voting with its method and returns the winners
+        // together with the round-by-round log
+        pub fn count_votes(subject: T::Hash) -> Result<CountResult, Error<T>> {
+            let settings =
+                <ActiveVotings<T>>::get(subject).ok_or(<Error<T>>::VotingSubjectDoesNotExist)?;
+            let ballots = BallotsView::new(subject, settings.submitted_height);
+            Ok(Self::count(&settings, &ballots))
+        }
+
+        pub fn calculate_alt_vote_winner(subject: T::Hash) -> Result<Candidate, Error<T>> {
+            Self::count_votes(subject)?
+                .winners
+                .into_iter()
+                .find(|(_, votes)| *votes > 0)
+                .map(|(winner, _)| winner)
+                .ok_or(<Error<T>>::NoValidBallots)
+        }
+
+        pub fn calculate_alt_vote_winners_list(
+            subject: T::Hash,
+        ) -> Result<BTreeMap<Candidate, u64>, Error<T>> {
+            Self::count_votes(subject).map(|result| result.winners)
+        }
+
+        pub fn get_election_results(subject: T::Hash) -> BTreeMap<T::BlockNumber, ElectionResult> {
+            <ElectionResults<T>>::iter_prefix(subject).collect()
+        }
+
+        pub fn get_last_election_result(subject: T::Hash) -> Option<ElectionResult> {
+            <ElectionResults<T>>::iter_prefix(subject)
+                .max_by_key(|(block_number, _)| *block_number)
+                .map(|(_, result)| result)
+        }
+
+        pub fn get_active_votings() -> BTreeMap<T::Hash, VotingInfo<T::BlockNumber>> {
+            <ActiveVotings<T>>::iter()
+                .map(|(subject, settings)| (subject, Self::voting_info(subject, settings)))
+                .collect()
+        }
+
+        pub fn get_active_voting(subject: T::Hash) -> Option<VotingInfo<T::BlockNumber>> {
+            <ActiveVotings<T>>::get(subject).map(|settings| Self::voting_info(subject, settings))
+        }
+
+        // the ballots are counted as if the voting ended now, the ballots of a secret voting
+        // are counted only once they are revealed
+        fn voting_info(
+            subject: T::Hash,
+            voting_settings: VotingSettings<T::BlockNumber>,
+        ) -> VotingInfo<T::BlockNumber> {
+            let tally = if voting_settings.candidates.is_empty() {
+                CountResult::default()
             } else {
-                <ActiveAltVoitings<T>>::insert(
-                    subject,
-                    AltVoutingSettings {
-                        voting_duration: duration,
-                        submitted_height: block_number,
-                        candidates,
-                        voted: 0,
-                        voters_number: 0,
-                    },
-                );
+                let ballots = BallotsView::<T>::new(subject, voting_settings.submitted_height);
+                Self::count(&voting_settings, &ballots)
+            };
+            let end = Self::voting_end(&voting_settings);
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            VotingInfo {
+                settings: voting_settings,
+                tally,
+                end,
+                remaining: end.saturating_sub(block_number),
             }
+        }
+    }
 
-            Ok(())
+    impl<T: Config> VotingTrait<T> for Pallet<T> {
+        fn create_voting(
+            subject: T::Hash,
+            duration: T::BlockNumber,
+            rules: VotingRules,
+            reveal_duration: Option<T::BlockNumber>,
+        ) -> Result<(), Error<T>> {
+            Self::create_alt_voting(
+                subject,
+                duration,
+                BTreeSet::new(),
+                0,
+                CountingMethod::Approval,
+                rules,
+                reveal_duration,
+            )
         }
 
-        fn create_alt_voting_list(
+        fn create_alt_voting(
             subject: T::Hash,
             duration: T::BlockNumber,
             candidates: BTreeSet<Candidate>,
             winners_amount: u32,
+            method: CountingMethod,
+            rules: VotingRules,
+            reveal_duration: Option<T::BlockNumber>,
         ) -> Result<(), Error<T>> {
             ensure!(
-                <ActiveAltListVoitings<T>>::get(subject) == None,
+                <ActiveVotings<T>>::get(subject) == None,
                 <Error<T>>::VotingHasBeenCreated
             );
 
-            let block_nummber = <frame_system::Pallet<T>>::block_number();
-            <ActiveAltListVoitings<T>>::insert(
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                !<StaleBallots<T>>::contains_key((subject, block_number)),
+                <Error<T>>::BallotsAreBeingRemoved
+            );
+            let end = block_number + duration + reveal_duration.unwrap_or_default();
+            Self::schedule(subject, end)?;
+            let mut tie_break_seed = TieBreakSeed::default();
+            let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+            let length = parent_hash.as_ref().len().min(tie_break_seed.len());
+            tie_break_seed[..length].copy_from_slice(&parent_hash.as_ref()[..length]);
+            <ActiveVotings<T>>::insert(
                 subject,
-                AltVotingListSettings {
+                VotingSettings {
+                    result: 0,
                     voting_duration: duration,
-                    submitted_height: block_nummber,
+                    submitted_height: block_number,
+                    voted: 0,
+                    rules,
                     candidates,
                     winners_amount,
+                    method,
+                    reveal_duration,
+                    tie_break_seed,
                 },
             );
+            Self::deposit_event(Event::VotingCreated(subject, end));
+
             Ok(())
         }
 
         fn vote(subject: T::Hash, power: u64) -> Result<(), Error<T>> {
             match <ActiveVotings<T>>::get(subject) {
                 Some(mut settings) => {
+                    ensure!(settings.reveal_duration.is_none(), <Error<T>>::SecretVoting);
+                    Self::ensure_voting_period(&settings)?;
+                    Self::ensure_not_all_voted(&settings)?;
                     settings.result += power;
-                    if settings.voted <= settings.voters_number {
-                        settings.voted += 1;
-                    }
+                    settings.voted = settings.voted.saturating_add(1);
+                    Self::close_if_all_voted(subject, &settings);
                     <ActiveVotings<T>>::insert(subject, settings);
+                    Self::deposit_event(Event::VoteCast(subject));
                     Ok(())
                 }
                 None => Err(<Error<T>>::VotingSubjectDoesNotExist),
@@ -405,59 +856,183 @@ pub mod pallet {
             ballot: AltVote,
             power: u64,
         ) -> Result<(), Error<T>> {
-            match <ActiveAltVoitings<T>>::get(subject) {
+            match <ActiveVotings<T>>::get(subject) {
                 Some(mut settings) => {
-                    let mut ballots_list = <BallotsStorage<T>>::get(subject);
-                    ballots_list.insert(account_id, (ballot, power));
-                    <BallotsStorage<T>>::insert(subject, ballots_list);
-                    if settings.voted <= settings.voters_number {
-                        settings.voted += 1;
-                        <ActiveAltVoitings<T>>::insert(subject, settings);
+                    ensure!(settings.reveal_duration.is_none(), <Error<T>>::SecretVoting);
+                    Self::ensure_voting_period(&settings)?;
+                    let ballot = Self::bound(&settings, ballot)?;
+                    let key = (subject, settings.submitted_height);
+                    // the voters can change their ballots, only the new voters are counted
+                    if !<BallotsStorage<T>>::contains_key(key, &account_id) {
+                        Self::ensure_not_all_voted(&settings)?;
+                        Self::count_voter(subject);
+                        settings.voted = settings.voted.saturating_add(1);
+                        Self::close_if_all_voted(subject, &settings);
+                        <ActiveVotings<T>>::insert(subject, settings);
                     }
+                    <BallotsStorage<T>>::insert(key, account_id, (ballot, power));
+                    Self::deposit_event(Event::VoteCast(subject));
                     Ok(())
                 }
                 None => Err(<Error<T>>::VotingSubjectDoesNotExist),
             }
         }
 
-        fn alt_vote_list(
+        fn commit_vote(
             subject: T::Hash,
             account_id: T::AccountId,
-            ballot: AltVote,
+            commitment: T::Hash,
             power: u64,
         ) -> Result<(), Error<T>> {
-            match <ActiveAltListVoitings<T>>::get(subject) {
-                Some(_) => {
-                    let mut ballots_list = <BallotsStorage<T>>::get(subject);
-                    ballots_list.insert(account_id, (ballot, power));
-                    <BallotsStorage<T>>::insert(subject, ballots_list);
-                    Ok(())
+            let settings =
+                <ActiveVotings<T>>::get(subject).ok_or(<Error<T>>::VotingSubjectDoesNotExist)?;
+            ensure!(
+                settings.reveal_duration.is_some(),
+                <Error<T>>::NotSecretVoting
+            );
+            Self::ensure_voting_period(&settings).map_err(|_| <Error<T>>::CommitPhaseIsOver)?;
+            let key = (subject, settings.submitted_height);
+            if !<Commitments<T>>::contains_key(key, &account_id) {
+                Self::count_voter(subject);
+            }
+            <Commitments<T>>::insert(key, account_id, (commitment, power));
+            Ok(())
+        }
+
+        fn reveal_vote(
+            subject: T::Hash,
+            account_id: T::AccountId,
+            ballot: AltVote,
+            salt: Vec<u8>,
+        ) -> Result<(), Error<T>> {
+            let mut settings =
+                <ActiveVotings<T>>::get(subject).ok_or(<Error<T>>::VotingSubjectDoesNotExist)?;
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                settings.reveal_duration.is_some()
+                    && block_number > settings.submitted_height + settings.voting_duration
+                    && block_number <= Self::voting_end(&settings),
+                <Error<T>>::NotRevealPhase
+            );
+            let key = (subject, settings.submitted_height);
+            let (commitment, power) =
+                <Commitments<T>>::get(key, &account_id).ok_or(<Error<T>>::CommitmentNotFound)?;
+            ensure!(
+                Self::commitment_of(&account_id, subject, &ballot, &salt) == commitment,
+                <Error<T>>::CommitmentMismatch
+            );
+            let ballot = Self::bound(&settings, ballot)?;
+            Self::ensure_not_all_voted(&settings)?;
+            <Commitments<T>>::remove(key, &account_id);
+
+            if settings.candidates.is_empty() {
+                settings.result += power;
+            } else {
+                <BallotsStorage<T>>::insert(key, account_id, (ballot, power));
+            }
+            settings.voted = settings.voted.saturating_add(1);
+            Self::close_if_all_voted(subject, &settings);
+            <ActiveVotings<T>>::insert(subject, settings);
+            Self::deposit_event(Event::VoteCast(subject));
+            Ok(())
+        }
+
+        fn delegate(
+            topic: DelegationTopic,
+            voter: T::AccountId,
+            delegate: T::AccountId,
+        ) -> Result<(), Error<T>> {
+            ensure!(voter != delegate, <Error<T>>::SelfDelegation);
+            let chain = Self::delegation_chain(topic, Some(delegate.clone()));
+            ensure!(!chain.contains(&voter), <Error<T>>::DelegationCycle);
+            // the voter moves with everyone the voter represents
+            let moved = Self::represented(topic, &voter).saturating_add(1);
+            let previous = <Delegations<T>>::get(topic, &voter);
+            let previous_chain = Self::delegation_chain(topic, previous.clone());
+            let kept: BTreeSet<_> = previous_chain.iter().collect();
+            ensure!(
+                chain
+                    .iter()
+                    .filter(|account| !kept.contains(account))
+                    .all(
+                        |account| Self::represented(topic, account).saturating_add(moved)
+                            <= T::MaxDelegators::get()
+                    ),
+                <Error<T>>::TooManyDelegators
+            );
+
+            for account in previous_chain.iter() {
+                <Represented<T>>::mutate(topic, account, |number| {
+                    *number = number.saturating_sub(moved)
+                });
+            }
+            for account in chain.iter() {
+                <Represented<T>>::mutate(topic, account, |number| {
+                    *number = number.saturating_add(moved)
+                });
+            }
+            if let Some(previous) = previous {
+                <Delegators<T>>::remove((topic, previous), &voter);
+            }
+            <Delegations<T>>::insert(topic, &voter, &delegate);
+            <Delegators<T>>::insert((topic, delegate.clone()), &voter, ());
+            Self::deposit_event(Event::Delegated(topic, voter, delegate));
+            Ok(())
+        }
+
+        fn undelegate(topic: DelegationTopic, voter: T::AccountId) -> Result<(), Error<T>> {
+            let delegate =
+                <Delegations<T>>::take(topic, &voter).ok_or(<Error<T>>::DelegationNotFound)?;
+            let moved = Self::represented(topic, &voter).saturating_add(1);
+            for account in Self::delegation_chain(topic, Some(delegate.clone())) {
+                <Represented<T>>::mutate(topic, account, |number| {
+                    *number = number.saturating_sub(moved)
+                });
+            }
+            <Delegators<T>>::remove((topic, delegate), &voter);
+            Self::deposit_event(Event::Undelegated(topic, voter));
+            Ok(())
+        }
+
+        fn delegated_voters<F: Fn(&T::AccountId) -> bool>(
+            topic: DelegationTopic,
+            delegate: &T::AccountId,
+            voted: F,
+        ) -> Vec<T::AccountId> {
+            let mut delegators = Vec::new();
+            let mut pending = sp_std::vec![delegate.clone()];
+            while let Some(account) = pending.pop() {
+                for (delegator, _) in <Delegators<T>>::iter_prefix((topic, account)) {
+                    if !voted(&delegator) {
+                        pending.push(delegator.clone());
+                        delegators.push(delegator);
+                    }
                 }
-                None => Err(<Error<T>>::VotingSubjectDoesNotExist),
             }
+            delegators
         }
     }
 }
 
 pub trait VotingTrait<T: Config> {
+    // a motion, the power of the votes is summed in `VotingSettings::result`,
+    // with `reveal_duration` the votes are committed and revealed after `duration`
     fn create_voting(
         subject: T::Hash,
         duration: T::BlockNumber,
-        all_voters: Option<u32>,
+        rules: VotingRules,
+        reveal_duration: Option<T::BlockNumber>,
     ) -> Result<(), Error<T>>;
 
+    // an election of `winners_amount` candidates counted with `method`
     fn create_alt_voting(
-        subject: T::Hash,
-        duration: T::BlockNumber,
-        candidates: BTreeSet<Candidate>,
-        all_voters: Option<u32>,
-    ) -> Result<(), Error<T>>;
-
-    fn create_alt_voting_list(
         subject: T::Hash,
         duration: T::BlockNumber,
         candidates: BTreeSet<Candidate>,
         winners_amount: u32,
+        method: CountingMethod,
+        rules: VotingRules,
+        reveal_duration: Option<T::BlockNumber>,
     ) -> Result<(), Error<T>>;
 
     fn vote(subject: T::Hash, power: u64) -> Result<(), Error<T>>;
@@ -469,12 +1044,41 @@ pub trait VotingTrait<T: Config> {
         power: u64,
     ) -> Result<(), Error<T>>;
 
-    fn alt_vote_list(
+    // submits `commitment_of(ballot, salt)` to a secret voting during the voting period
+    fn commit_vote(
         subject: T::Hash,
         account_id: T::AccountId,
-        ballot: AltVote,
+        commitment: T::Hash,
         power: u64,
     ) -> Result<(), Error<T>>;
+
+    // reveals the committed ballot during the reveal window, the ballot of a motion is empty
+    fn reveal_vote(
+        subject: T::Hash,
+        account_id: T::AccountId,
+        ballot: AltVote,
+        salt: Vec<u8>,
+    ) -> Result<(), Error<T>>;
+
+    // the voter is represented by the delegate on the topic until the voter votes,
+    // the delegation applies to the votes the delegate casts after it, a vote the delegate
+    // has already cast is not recounted and the voter can still vote
+    fn delegate(
+        topic: DelegationTopic,
+        voter: T::AccountId,
+        delegate: T::AccountId,
+    ) -> Result<(), Error<T>>;
+
+    fn undelegate(topic: DelegationTopic, voter: T::AccountId) -> Result<(), Error<T>>;
+
+    // the voters represented by the delegate directly or through the other delegates,
+    // the voters for whom `voted` holds vote for themselves and for their delegators,
+    // at most `MaxDelegators`
+    fn delegated_voters<F: Fn(&T::AccountId) -> bool>(
+        topic: DelegationTopic,
+        delegate: &T::AccountId,
+        voted: F,
+    ) -> Vec<T::AccountId>;
 }
 
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
@@ -483,27 +1087,98 @@ pub struct VotingSettings<BlockNumber> {
     pub result: u64,
     pub voting_duration: BlockNumber,
     pub submitted_height: BlockNumber,
+    // the number of the voters who voted
     pub voted: u32,
-    pub voters_number: u32,
+    pub rules: VotingRules,
+    pub candidates: BTreeSet<Candidate>,
+    pub winners_amount: u32,
+    pub method: CountingMethod,
+    // the length of the reveal window after `voting_duration` of a secret voting
+    pub reveal_duration: Option<BlockNumber>,
+    // the seed of the final tie-breaking of the count, fixed when the voting is created
+    pub tie_break_seed: TieBreakSeed,
+}
+
+impl<BlockNumber> VotingSettings<BlockNumber> {
+    pub fn all_voted(&self) -> bool {
+        self.rules.voters_number > 0 && self.voted >= self.rules.voters_number
+    }
+
+    // the voting is decided only when both the quorum and the turnout are reached
+    pub fn quorum_reached(&self) -> bool {
+        self.voted >= self.rules.quorum
+            && self.voted >= self.rules.turnout.mul_ceil(self.rules.voters_number)
+    }
 }
 
+// an active voting as it is exposed by the runtime api
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct AltVoutingSettings<BlockNumber> {
-    pub voting_duration: BlockNumber,
-    pub submitted_height: BlockNumber,
-    pub candidates: BTreeSet<Candidate>,
-    pub voted: u32,
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug)]
+pub struct VotingInfo<BlockNumber> {
+    // the power of a motion is tallied in `settings.result`
+    pub settings: VotingSettings<BlockNumber>,
+    // the current count of the ballots of a voting with candidates
+    pub tally: CountResult,
+    // the last block of the voting, including the reveal window
+    pub end: BlockNumber,
+    // the blocks left until `end`
+    pub remaining: BlockNumber,
+}
+
+// the votings in which the citizens can be represented by their delegates
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum DelegationTopic {
+    Referendums,
+    AssemblyElections,
+}
+
+// the rules a voting is decided by
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug, Default)]
+pub struct VotingRules {
+    // the number of the voters who can vote, 0 when they are not known
     pub voters_number: u32,
+    // the least number of the voters
+    pub quorum: u32,
+    // the least share of `voters_number` who have to vote
+    pub turnout: Percent,
+    // the voting ends as soon as all of `voters_number` voted
+    pub early_close: bool,
+}
+
+impl VotingRules {
+    // anyone can vote until the end of the voting, it is always decided
+    pub fn open() -> Self {
+        Default::default()
+    }
+
+    // the known voters, the voting ends as soon as all of them voted
+    pub fn all_voters(voters_number: u32) -> Self {
+        Self {
+            voters_number,
+            early_close: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_quorum(self, quorum: u32) -> Self {
+        Self { quorum, ..self }
+    }
+
+    pub fn with_turnout(self, turnout: Percent) -> Self {
+        Self { turnout, ..self }
+    }
 }
 
+// the count of a finalized voting with candidates, kept for the audit of the elections
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct AltVotingListSettings<BlockNumber> {
-    pub voting_duration: BlockNumber,
-    pub submitted_height: BlockNumber,
+pub struct ElectionResult {
+    pub method: CountingMethod,
     pub candidates: BTreeSet<Candidate>,
     pub winners_amount: u32,
+    pub count: CountResult,
 }
 
 pub type Candidate = Vec<u8>;
@@ -512,10 +1187,31 @@ pub type Candidate = Vec<u8>;
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug, Default)]
 pub struct AltVote {
     pub content: VecDeque<Candidate>,
+    // scores of the candidates in `content` for the `Score` method
+    pub scores: Vec<u32>,
 }
 
 impl AltVote {
     pub fn new(content: VecDeque<Candidate>) -> Self {
-        Self { content }
+        Self {
+            content,
+            scores: Vec::new(),
+        }
+    }
+
+    pub fn with_scores(content: VecDeque<Candidate>, scores: Vec<u32>) -> Self {
+        Self { content, scores }
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait VotingPalletApi<T: Config> {
+        fn get_election_results(subject: T::Hash) -> BTreeMap<T::BlockNumber, ElectionResult>;
+
+        fn get_last_election_result(subject: T::Hash) -> Option<ElectionResult>;
+
+        fn get_active_votings() -> BTreeMap<T::Hash, VotingInfo<T::BlockNumber>>;
+
+        fn get_active_voting(subject: T::Hash) -> Option<VotingInfo<T::BlockNumber>>;
     }
 }
@@ -1,5 +1,5 @@
-use crate::{self as pallet_voting, Candidate};
-use frame_support::parameter_types;
+use crate::{self as pallet_voting, BallotsView, Candidate, CountResult, VotingSettings};
+use frame_support::{parameter_types, weights::RuntimeDbWeight};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
@@ -8,6 +8,7 @@ use sp_runtime::{
 };
 use sp_std::collections::btree_set::BTreeSet;
 use sp_std::collections::vec_deque::VecDeque;
+use std::cell::RefCell;
 use system::ensure_signed;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -21,20 +22,23 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-        VotingPallet: pallet_voting::{Pallet, Call, Storage},
+        VotingPallet: pallet_voting::{Pallet, Call, Storage, Event<T>},
     }
 );
 
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
     pub const SS58Prefix: u8 = 42;
+    pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 10 };
+    pub BlockWeights: frame_system::limits::BlockWeights =
+        frame_system::limits::BlockWeights::simple_max(1_000);
 }
 
 impl system::Config for Test {
     type BaseCallFilter = ();
-    type BlockWeights = ();
+    type BlockWeights = BlockWeights;
     type BlockLength = ();
-    type DbWeight = ();
+    type DbWeight = DbWeight;
     type Origin = Origin;
     type Call = Call;
     type Index = u64;
@@ -56,10 +60,45 @@ impl system::Config for Test {
     type OnSetCode = ();
 }
 
+parameter_types! {
+    pub const MaxPreferences: u32 = 16;
+    pub const MaxCandidateLength: u32 = 32;
+    pub const MaxFinalizationsPerBlock: u32 = 2;
+    pub const MaxBallotRemovalsPerBlock: u32 = 8;
+    pub const MaxDelegators: u32 = 3;
+}
+
+thread_local! {
+    static FINALIZED: RefCell<Vec<H256>> = RefCell::new(Vec::new());
+}
+
+// records the dispatched subjects
+pub struct FinalizeRecorder;
+
+impl pallet_voting::FinalizeVotingDispatchTrait<Test> for FinalizeRecorder {
+    fn finalize_voting(
+        subject: H256,
+        _voting_settings: VotingSettings<u64>,
+        _result: CountResult,
+        _ballots: &BallotsView<Test>,
+    ) {
+        FINALIZED.with(|finalized| finalized.borrow_mut().push(subject));
+    }
+}
+
+// the subjects dispatched since the last call
+pub fn take_finalized() -> Vec<H256> {
+    FINALIZED.with(|finalized| finalized.take())
+}
+
 impl pallet_voting::Config for Test {
-    type FinalizeVotingDispatch = ();
-    type FinalizeAltVotingDispatch = ();
-    type FinalizeAltVotingListDispatch = ();
+    type Event = Event;
+    type FinalizeVotingDispatch = FinalizeRecorder;
+    type MaxPreferences = MaxPreferences;
+    type MaxCandidateLength = MaxCandidateLength;
+    type MaxFinalizationsPerBlock = MaxFinalizationsPerBlock;
+    type MaxBallotRemovalsPerBlock = MaxBallotRemovalsPerBlock;
+    type MaxDelegators = MaxDelegators;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -199,3 +238,13 @@ pub fn get_mock_subjects() -> BTreeSet<Candidate> {
     });
     res
 }
+
+// builds a ballot from the ranked candidates ids, the candidate `n` is `[n; 32]`
+pub fn alt_vote(ranking: &[u8]) -> pallet_voting::AltVote {
+    let content: VecDeque<Candidate> = ranking.iter().map(|id| [*id; 32].to_vec()).collect();
+    pallet_voting::AltVote::new(content)
+}
+
+pub fn candidates(ids: &[u8]) -> BTreeSet<Candidate> {
+    ids.iter().map(|id| [*id; 32].to_vec()).collect()
+}
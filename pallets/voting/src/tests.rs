@@ -1,8 +1,15 @@
 use crate::mock::*;
 use crate::*;
 use assert::assert_err;
-use frame_support::{assert_ok, traits::OnFinalize};
-use sp_runtime::traits::Hash;
+use frame_support::{
+    assert_ok,
+    traits::{OnFinalize, OnInitialize},
+};
+use proptest::{prop_assert, prop_assert_eq, proptest};
+use sp_runtime::{traits::Hash, DispatchError, Percent};
+
+// the tie-breaking seed of the counts, it orders the candidates 1, 2 and 3 by their ids
+const SEED: TieBreakSeed = [1; 32];
 
 #[test]
 fn basic_voting_test() {
@@ -13,10 +20,20 @@ fn basic_voting_test() {
         let duration = 100;
 
         assert!(VotingPallet::active_votings(subject.clone()).is_none());
-        assert_ok!(VotingPallet::create_voting(subject.clone(), duration, None));
+        assert_ok!(VotingPallet::create_voting(
+            subject.clone(),
+            duration,
+            VotingRules::open(),
+            None
+        ));
         assert!(VotingPallet::active_votings(subject.clone()).is_some());
 
-        assert_err!(VotingPallet::create_voting(subject.clone(), duration, None));
+        assert_err!(VotingPallet::create_voting(
+            subject.clone(),
+            duration,
+            VotingRules::open(),
+            None
+        ));
 
         assert_err!(VotingPallet::vote(Hashing::hash(&[2; 32]), 1));
 
@@ -25,7 +42,12 @@ fn basic_voting_test() {
         VotingPallet::on_finalize(duration);
 
         assert!(VotingPallet::active_votings(subject.clone()).is_none());
-        assert_ok!(VotingPallet::create_voting(subject.clone(), duration, None));
+        assert_ok!(VotingPallet::create_voting(
+            subject.clone(),
+            duration,
+            VotingRules::open(),
+            None
+        ));
         assert!(VotingPallet::active_votings(subject.clone()).is_some());
     });
 }
@@ -40,14 +62,17 @@ fn basic_alt_voting_test() {
         let subject = Hashing::hash(&[1; 32]);
         let duration = 100;
 
-        assert!(VotingPallet::active_alt_votings(subject.clone()).is_none());
+        assert!(VotingPallet::active_votings(subject.clone()).is_none());
         assert_ok!(VotingPallet::create_alt_voting(
             subject.clone(),
             duration,
             subjects_list.clone(),
+            1,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
             None
         ));
-        assert!(VotingPallet::active_alt_votings(subject.clone()).is_some());
+        assert!(VotingPallet::active_votings(subject.clone()).is_some());
 
         ballots_list.iter().for_each(|ballot| {
             let power = 1;
@@ -66,14 +91,17 @@ fn basic_alt_voting_test() {
 
         VotingPallet::on_finalize(duration);
 
-        assert!(VotingPallet::active_alt_votings(subject.clone()).is_none());
+        assert!(VotingPallet::active_votings(subject.clone()).is_none());
         assert_ok!(VotingPallet::create_alt_voting(
             subject.clone(),
             duration,
             subjects_list.clone(),
+            1,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
             None
         ));
-        assert!(VotingPallet::active_alt_votings(subject.clone()).is_some());
+        assert!(VotingPallet::active_votings(subject.clone()).is_some());
     });
 }
 
@@ -87,18 +115,21 @@ fn basic_alt_voting_list_test() {
         let subject = Hashing::hash(&[1; 32]);
         let duration = 100;
 
-        assert!(VotingPallet::active_alt_list_votings(subject.clone()).is_none());
-        assert_ok!(VotingPallet::create_alt_voting_list(
+        assert!(VotingPallet::active_votings(subject.clone()).is_none());
+        assert_ok!(VotingPallet::create_alt_voting(
             subject.clone(),
             duration,
             subjects_list.clone(),
             2,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
+            None
         ));
-        assert!(VotingPallet::active_alt_list_votings(subject.clone()).is_some());
+        assert!(VotingPallet::active_votings(subject.clone()).is_some());
 
         ballots_list.iter().for_each(|ballot| {
             let power = 1;
-            assert_ok!(VotingPallet::alt_vote_list(
+            assert_ok!(VotingPallet::alt_vote(
                 subject.clone(),
                 ballot.0,
                 ballot.1.clone(),
@@ -115,14 +146,17 @@ fn basic_alt_voting_list_test() {
         );
         VotingPallet::on_finalize(duration);
 
-        assert!(VotingPallet::active_alt_list_votings(subject.clone()).is_none());
-        assert_ok!(VotingPallet::create_alt_voting_list(
+        assert!(VotingPallet::active_votings(subject.clone()).is_none());
+        assert_ok!(VotingPallet::create_alt_voting(
             subject.clone(),
             duration,
             subjects_list.clone(),
             2,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
+            None
         ));
-        assert!(VotingPallet::active_alt_list_votings(subject.clone()).is_some());
+        assert!(VotingPallet::active_votings(subject.clone()).is_some());
     });
 }
 
@@ -135,85 +169,88 @@ fn alt_vote_list_teset_with_power() {
         let subject = Hashing::hash(&[1; 32]);
         let duration = 100;
 
-        assert!(VotingPallet::active_alt_list_votings(subject.clone()).is_none());
-        assert_ok!(VotingPallet::create_alt_voting_list(
+        assert!(VotingPallet::active_votings(subject.clone()).is_none());
+        assert_ok!(VotingPallet::create_alt_voting(
             subject.clone(),
             duration,
             subjects_list.clone(),
             2,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
+            None
         ));
-        assert!(VotingPallet::active_alt_list_votings(subject.clone()).is_some());
+        assert!(VotingPallet::active_votings(subject.clone()).is_some());
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[0].0.clone(),
             ballots_list[0].1.clone(),
             10
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[1].0.clone(),
             ballots_list[1].1.clone(),
             10
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[2].0.clone(),
             ballots_list[2].1.clone(),
             10
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[3].0.clone(),
             ballots_list[3].1.clone(),
             5
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[4].0.clone(),
             ballots_list[4].1.clone(),
             5
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[5].0.clone(),
             ballots_list[5].1.clone(),
             5
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[6].0.clone(),
             ballots_list[6].1.clone(),
             1
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[7].0.clone(),
             ballots_list[7].1.clone(),
             1
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[8].0.clone(),
             ballots_list[8].1.clone(),
             1
         ));
 
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[9].0.clone(),
             ballots_list[9].1.clone(),
             1
         ));
-        assert_ok!(VotingPallet::alt_vote_list(
+        assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
             ballots_list[10].0.clone(),
             ballots_list[10].1.clone(),
@@ -240,14 +277,17 @@ fn alt_vote_teset_with_power() {
         let subject = Hashing::hash(&[1; 32]);
         let duration = 100;
 
-        assert!(VotingPallet::active_alt_votings(subject.clone()).is_none());
+        assert!(VotingPallet::active_votings(subject.clone()).is_none());
         assert_ok!(VotingPallet::create_alt_voting(
             subject.clone(),
             duration,
             subjects_list.clone(),
+            1,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
             None
         ));
-        assert!(VotingPallet::active_alt_votings(subject.clone()).is_some());
+        assert!(VotingPallet::active_votings(subject.clone()).is_some());
 
         assert_ok!(VotingPallet::alt_vote(
             subject.clone(),
@@ -333,3 +373,1218 @@ fn alt_vote_teset_with_power() {
         );
     });
 }
+
+#[test]
+fn irv_reference_vector_test() {
+    // Tennessee capital election: Memphis (1), Nashville (2), Chattanooga (3), Knoxville (4).
+    // Chattanooga and then Nashville are eliminated, Knoxville wins with 58%.
+    let ballots = vec![
+        (alt_vote(&[1, 2, 3, 4]), 42),
+        (alt_vote(&[2, 3, 4, 1]), 26),
+        (alt_vote(&[3, 4, 2, 1]), 15),
+        (alt_vote(&[4, 3, 2, 1]), 17),
+    ];
+    assert_eq!(
+        counting::instant_runoff(&candidates(&[1, 2, 3, 4]), &ballots, &SEED),
+        Some([4_u8; 32].to_vec())
+    );
+
+    // first preferences majority wins in the first round
+    let ballots = vec![(alt_vote(&[1, 2]), 51), (alt_vote(&[2, 1]), 49)];
+    assert_eq!(
+        counting::instant_runoff(&candidates(&[1, 2]), &ballots, &SEED),
+        Some([1_u8; 32].to_vec())
+    );
+}
+
+#[test]
+fn irv_transfers_skip_eliminated_candidates_test() {
+    // 2 is eliminated first, then the ballots of 4 skip 2 and are transferred to 3.
+    // 1 and 3 are tied at the end, 3 had fewer votes in the previous round and is eliminated.
+    let ballots = vec![
+        (alt_vote(&[1]), 5),
+        (alt_vote(&[2, 1]), 2),
+        (alt_vote(&[3]), 4),
+        (alt_vote(&[4, 2, 3]), 3),
+    ];
+    let candidates = candidates(&[1, 2, 3, 4]);
+    assert_eq!(
+        counting::instant_runoff(&candidates, &ballots, &SEED),
+        Some([1_u8; 32].to_vec())
+    );
+
+    let mut tallies = BTreeMap::new();
+    tallies.insert([1_u8; 32].to_vec(), 7);
+    tallies.insert([3_u8; 32].to_vec(), 7);
+    assert_eq!(
+        counting::eliminate_to(&candidates, 2, &ballots, &SEED).winners,
+        tallies
+    );
+}
+
+#[test]
+fn irv_exhausted_ballots_test() {
+    // ballots of 3 do not rank anyone else and are exhausted after its elimination,
+    // 1 wins with the majority of the continuing votes
+    let ballots = vec![
+        (alt_vote(&[1]), 4),
+        (alt_vote(&[2]), 3),
+        (alt_vote(&[3]), 2),
+    ];
+    let candidates = candidates(&[1, 2, 3]);
+    assert_eq!(
+        counting::instant_runoff(&candidates, &ballots, &SEED),
+        Some([1_u8; 32].to_vec())
+    );
+
+    let mut tallies = BTreeMap::new();
+    tallies.insert([1_u8; 32].to_vec(), 4);
+    tallies.insert([2_u8; 32].to_vec(), 3);
+    assert_eq!(
+        counting::eliminate_to(&candidates, 2, &ballots, &SEED).winners,
+        tallies
+    );
+
+    // unknown and repeated preferences are skipped
+    let ballots = vec![(alt_vote(&[5, 2, 2, 1]), 3), (alt_vote(&[1]), 2)];
+    assert_eq!(
+        counting::instant_runoff(&candidates, &ballots, &SEED),
+        Some([2_u8; 32].to_vec())
+    );
+
+    assert_eq!(counting::instant_runoff(&candidates, &[], &SEED), None);
+    assert_eq!(
+        counting::instant_runoff(&candidates, &[(alt_vote(&[5]), 1)], &SEED),
+        None
+    );
+}
+
+#[test]
+fn irv_tie_breaking_test() {
+    // tied in every round, the candidate with the greater key of the seed is eliminated,
+    // so the seed of the voting decides the tie and not the ids
+    let ballots = vec![(alt_vote(&[2]), 1), (alt_vote(&[1]), 1)];
+    let key = |seed: &TieBreakSeed, id: u8| counting::tie_break_key(seed, &[id; 32].to_vec());
+    assert!(key(&SEED, 1) < key(&SEED, 2));
+    assert_eq!(
+        counting::instant_runoff(&candidates(&[1, 2]), &ballots, &SEED),
+        Some([1_u8; 32].to_vec())
+    );
+    let other_seed = [0; 32];
+    assert!(key(&other_seed, 1) > key(&other_seed, 2));
+    assert_eq!(
+        counting::instant_runoff(&candidates(&[1, 2]), &ballots, &other_seed),
+        Some([2_u8; 32].to_vec())
+    );
+
+    // 2 and 3 are tied for the last place in the second round, 2 had fewer votes in the first one
+    let ballots = vec![
+        (alt_vote(&[1]), 6),
+        (alt_vote(&[2]), 2),
+        (alt_vote(&[3]), 3),
+        (alt_vote(&[4, 2]), 1),
+        (alt_vote(&[5, 1]), 5),
+    ];
+    let mut tallies = BTreeMap::new();
+    tallies.insert([1_u8; 32].to_vec(), 6);
+    tallies.insert([3_u8; 32].to_vec(), 3);
+    tallies.insert([5_u8; 32].to_vec(), 5);
+    assert_eq!(
+        counting::eliminate_to(&candidates(&[1, 2, 3, 4, 5]), 3, &ballots, &SEED).winners,
+        tallies
+    );
+}
+
+#[test]
+fn alt_voting_without_ballots_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let subject = Hashing::hash(&[1; 32]);
+
+        assert_ok!(VotingPallet::create_alt_voting(
+            subject,
+            100,
+            get_mock_subjects(),
+            1,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
+            None
+        ));
+        assert_err!(VotingPallet::calculate_alt_vote_winner(subject));
+        assert_err!(VotingPallet::alt_vote(subject, 1, alt_vote(&[7]), 1));
+        assert_err!(VotingPallet::calculate_alt_vote_winner(subject));
+        assert_ok!(VotingPallet::alt_vote(subject, 2, alt_vote(&[2]), 1));
+        assert_eq!(
+            VotingPallet::calculate_alt_vote_winner(subject).unwrap(),
+            [2_u8; 32].to_vec()
+        );
+    });
+}
+
+#[test]
+fn stv_reference_vector_test() {
+    // Oranges (1), Pears (2), Chocolate (3), Strawberries (4), Sweets (5) for 3 seats,
+    // 20 votes and the quota is 6. The surplus of Chocolate is transferred at half value.
+    let ballots = vec![
+        (alt_vote(&[1]), 4),
+        (alt_vote(&[2, 1]), 2),
+        (alt_vote(&[3, 4]), 8),
+        (alt_vote(&[3, 5]), 4),
+        (alt_vote(&[4]), 1),
+        (alt_vote(&[5]), 1),
+    ];
+    let result =
+        counting::single_transferable_vote(&candidates(&[1, 2, 3, 4, 5]), 3, &ballots, &SEED);
+
+    let mut winners = BTreeMap::new();
+    winners.insert([1_u8; 32].to_vec(), 6);
+    winners.insert([3_u8; 32].to_vec(), 12);
+    winners.insert([4_u8; 32].to_vec(), 5);
+    assert_eq!(result.winners, winners);
+
+    let actions: Vec<CountAction> = result
+        .rounds
+        .iter()
+        .map(|round| round.action.clone())
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            CountAction::Elected([3_u8; 32].to_vec()),
+            CountAction::Eliminated([2_u8; 32].to_vec()),
+            CountAction::Elected([1_u8; 32].to_vec()),
+            CountAction::Eliminated([5_u8; 32].to_vec()),
+            CountAction::Elected([4_u8; 32].to_vec()),
+        ]
+    );
+
+    let mut transfers = BTreeMap::new();
+    transfers.insert([4_u8; 32].to_vec(), 4);
+    transfers.insert([5_u8; 32].to_vec(), 2);
+    assert_eq!(result.rounds[0].transfers, transfers);
+    // ballots of Sweets do not rank anyone else
+    assert_eq!(result.rounds[3].exhausted, 3);
+}
+
+#[test]
+fn stv_fractional_surplus_test() {
+    // 1 is elected with 7 votes over the quota of 4, its ballots are transferred at 3/7 of
+    // their value: 2 gets 5 * 3/7 and 3 gets 2 * 3/7, so 2 is eliminated before 3
+    let ballots = vec![
+        (alt_vote(&[1, 2]), 5),
+        (alt_vote(&[1, 3]), 2),
+        (alt_vote(&[2]), 1),
+        (alt_vote(&[3]), 3),
+    ];
+    let result = counting::single_transferable_vote(&candidates(&[1, 2, 3]), 2, &ballots, &SEED);
+
+    let mut winners = BTreeMap::new();
+    winners.insert([1_u8; 32].to_vec(), 7);
+    winners.insert([3_u8; 32].to_vec(), 3);
+    assert_eq!(result.winners, winners);
+    assert_eq!(
+        result.rounds[1].action,
+        CountAction::Eliminated([2_u8; 32].to_vec())
+    );
+}
+
+#[test]
+fn alt_voting_with_stv_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let subject = Hashing::hash(&[1; 32]);
+
+        assert_ok!(VotingPallet::create_alt_voting(
+            subject,
+            100,
+            candidates(&[1, 2, 3, 4, 5]),
+            3,
+            CountingMethod::SingleTransferableVote,
+            VotingRules::open(),
+            None
+        ));
+        let ballots = vec![
+            (alt_vote(&[1]), 4),
+            (alt_vote(&[2, 1]), 2),
+            (alt_vote(&[3, 4]), 8),
+            (alt_vote(&[3, 5]), 4),
+            (alt_vote(&[4]), 1),
+            (alt_vote(&[5]), 1),
+        ];
+        ballots
+            .into_iter()
+            .enumerate()
+            .for_each(|(voter, (ballot, power))| {
+                assert_ok!(VotingPallet::alt_vote(subject, voter as u64, ballot, power));
+            });
+
+        let result = VotingPallet::count_votes(subject).unwrap();
+        assert_eq!(result.rounds.len(), 5);
+        assert_eq!(
+            VotingPallet::calculate_alt_vote_winners_list(subject).unwrap(),
+            result.winners
+        );
+        assert_eq!(
+            result.winners.keys().cloned().collect::<Vec<_>>(),
+            vec![
+                [1_u8; 32].to_vec(),
+                [3_u8; 32].to_vec(),
+                [4_u8; 32].to_vec()
+            ]
+        );
+    });
+}
+
+#[test]
+fn single_pass_methods_test() {
+    let ballots = vec![
+        (alt_vote(&[1, 2]), 3),
+        (alt_vote(&[2, 3]), 2),
+        (alt_vote(&[3, 2]), 2),
+    ];
+    let candidates = candidates(&[1, 2, 3]);
+    let winners = |method: CountingMethod, winners_amount: u32| -> Vec<(u8, u64)> {
+        method
+            .count(&candidates, winners_amount, &ballots, &SEED)
+            .winners
+            .into_iter()
+            .map(|(candidate, votes)| (candidate[0], votes))
+            .collect()
+    };
+
+    // 2 and 3 are tied for the second place, the candidate with the lesser key wins
+    assert_eq!(winners(CountingMethod::Plurality, 1), vec![(1, 3)]);
+    assert_eq!(winners(CountingMethod::Plurality, 2), vec![(1, 3), (2, 2)]);
+    assert_eq!(winners(CountingMethod::Approval, 1), vec![(2, 7)]);
+    assert_eq!(winners(CountingMethod::Approval, 2), vec![(2, 7), (3, 4)]);
+    // the first preference gets 2 points and the second one gets 1 point
+    assert_eq!(winners(CountingMethod::Borda, 1), vec![(2, 9)]);
+
+    let result = CountingMethod::Plurality.count(&candidates, 2, &ballots, &SEED);
+    assert_eq!(
+        result.rounds[0].action,
+        CountAction::Elected([1_u8; 32].to_vec())
+    );
+    assert_eq!(result.rounds[1].tallies.len(), 2);
+}
+
+#[test]
+fn score_method_test() {
+    let scored = |ranking: &[u8], scores: &[u32]| {
+        let ballot = alt_vote(ranking);
+        AltVote::with_scores(ballot.content, scores.to_vec())
+    };
+    // scores above the maximum are capped, unknown candidates are skipped
+    let ballots = vec![
+        (scored(&[1, 2], &[10, 4]), 1),
+        (scored(&[2, 3], &[7, 12]), 2),
+        (scored(&[5, 1], &[10, 3]), 1),
+    ];
+    let result = voting_method::Score::count(&candidates(&[1, 2, 3]), 2, &ballots, &SEED);
+
+    let mut winners = BTreeMap::new();
+    winners.insert([3_u8; 32].to_vec(), 20);
+    winners.insert([2_u8; 32].to_vec(), 18);
+    assert_eq!(result.winners, winners);
+    assert_eq!(result.rounds[1].tallies.get(&[1_u8; 32][..]), Some(&13));
+}
+
+#[test]
+fn voting_registry_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let motion = Hashing::hash(&[1; 32]);
+        let election = Hashing::hash(&[2; 32]);
+
+        // motions and elections share the registry
+        assert_ok!(VotingPallet::create_voting(
+            motion,
+            100,
+            VotingRules::open(),
+            None
+        ));
+        assert_err!(VotingPallet::create_alt_voting(
+            motion,
+            100,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            None
+        ));
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            100,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::all_voters(2),
+            None
+        ));
+
+        assert_ok!(VotingPallet::alt_vote(election, 1, alt_vote(&[2]), 1));
+        VotingPallet::on_finalize(1);
+        assert!(VotingPallet::active_votings(election).is_some());
+
+        // the election closes early once all the voters voted, the motion waits for its end
+        assert_ok!(VotingPallet::alt_vote(election, 2, alt_vote(&[2, 1]), 1));
+        assert_eq!(
+            VotingPallet::calculate_alt_vote_winner(election).unwrap(),
+            [2_u8; 32].to_vec()
+        );
+        VotingPallet::on_finalize(2);
+        assert!(VotingPallet::active_votings(election).is_none());
+        assert!(VotingPallet::active_votings(motion).is_some());
+
+        VotingPallet::on_finalize(100);
+        assert!(VotingPallet::active_votings(motion).is_none());
+    });
+}
+
+#[test]
+fn election_results_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let election = Hashing::hash(&[1; 32]);
+        let motion = Hashing::hash(&[2; 32]);
+
+        assert_ok!(VotingPallet::create_voting(
+            motion,
+            10,
+            VotingRules::open(),
+            None
+        ));
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            10,
+            candidates(&[1, 2, 3]),
+            1,
+            CountingMethod::InstantRunoff,
+            VotingRules::open(),
+            None
+        ));
+        assert_ok!(VotingPallet::alt_vote(election, 1, alt_vote(&[1]), 3));
+        assert_ok!(VotingPallet::alt_vote(election, 2, alt_vote(&[2]), 2));
+        assert_ok!(VotingPallet::alt_vote(election, 3, alt_vote(&[3, 2]), 2));
+        VotingPallet::on_finalize(10);
+
+        // 3 is eliminated and its ballot is transferred to 2
+        let result = VotingPallet::get_last_election_result(election).unwrap();
+        assert_eq!(result.method, CountingMethod::InstantRunoff);
+        assert_eq!(
+            result.count.winners.keys().next(),
+            Some(&[2_u8; 32].to_vec())
+        );
+        assert_eq!(
+            result.count.rounds[0].action,
+            CountAction::Eliminated([3_u8; 32].to_vec())
+        );
+        assert_eq!(
+            result.count.rounds[0].transfers.get(&[2_u8; 32][..]),
+            Some(&2)
+        );
+        assert_eq!(VotingPallet::election_results(election, 10), Some(result));
+        assert!(VotingPallet::get_election_results(motion).is_empty());
+
+        // the subject of a periodic election keeps the results of every term
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            10,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            None
+        ));
+        VotingPallet::on_finalize(20);
+        assert_eq!(VotingPallet::get_election_results(election).len(), 2);
+        assert_eq!(
+            VotingPallet::get_last_election_result(election).map(|result| result.method),
+            Some(CountingMethod::Plurality)
+        );
+    });
+}
+
+#[test]
+fn secret_voting_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let election = Hashing::hash(&[1; 32]);
+        let motion = Hashing::hash(&[2; 32]);
+
+        assert_ok!(VotingPallet::create_voting(
+            motion,
+            10,
+            VotingRules::open(),
+            Some(5)
+        ));
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            10,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            Some(5)
+        ));
+
+        // the secret votings do not take the open ballots
+        assert_err!(VotingPallet::vote(motion, 1));
+        assert_err!(VotingPallet::alt_vote(election, 1, alt_vote(&[1]), 1));
+
+        let salt = b"salt".to_vec();
+        assert_ok!(VotingPallet::commit_vote(
+            motion,
+            1,
+            VotingPallet::commitment_of(&1, motion, &AltVote::default(), &salt),
+            4
+        ));
+        assert_ok!(VotingPallet::commit_vote(
+            election,
+            1,
+            VotingPallet::commitment_of(&1, election, &alt_vote(&[1]), &salt),
+            3
+        ));
+        assert_ok!(VotingPallet::commit_vote(
+            election,
+            2,
+            VotingPallet::commitment_of(&2, election, &alt_vote(&[2]), &salt),
+            2
+        ));
+        assert_ok!(VotingPallet::commit_vote(
+            election,
+            3,
+            VotingPallet::commitment_of(&3, election, &alt_vote(&[2]), &salt),
+            2
+        ));
+
+        // the commitment of another voter can not be revealed
+        assert_ok!(VotingPallet::commit_vote(
+            election,
+            5,
+            VotingPallet::commitment_of(&2, election, &alt_vote(&[2]), &salt),
+            1
+        ));
+
+        // the ballots are revealed only after the voting period
+        assert_err!(VotingPallet::reveal_vote(
+            election,
+            1,
+            alt_vote(&[1]),
+            salt.clone()
+        ));
+        VotingPallet::on_finalize(10);
+        assert!(VotingPallet::active_votings(election).is_some());
+
+        frame_system::Pallet::<Test>::set_block_number(11);
+        assert_err!(VotingPallet::commit_vote(
+            election,
+            4,
+            VotingPallet::commitment_of(&4, election, &alt_vote(&[2]), &salt),
+            1
+        ));
+        assert_err!(VotingPallet::reveal_vote(
+            election,
+            1,
+            alt_vote(&[2]),
+            salt.clone()
+        ));
+        assert_err!(VotingPallet::reveal_vote(
+            election,
+            4,
+            alt_vote(&[2]),
+            salt.clone()
+        ));
+        assert_ok!(VotingPallet::reveal_vote(
+            motion,
+            1,
+            AltVote::default(),
+            salt.clone()
+        ));
+        assert_ok!(VotingPallet::reveal_vote(
+            election,
+            1,
+            alt_vote(&[1]),
+            salt.clone()
+        ));
+        assert_ok!(VotingPallet::reveal_vote(
+            election,
+            2,
+            alt_vote(&[2]),
+            salt.clone()
+        ));
+        assert_eq!(VotingPallet::active_votings(motion).unwrap().result, 4);
+        assert_eq!(
+            VotingPallet::reveal_vote(election, 5, alt_vote(&[2]), salt.clone())
+                .map_err(DispatchError::from),
+            Err(<Error<Test>>::CommitmentMismatch.into())
+        );
+
+        // the ballot of 3 is not revealed and is excluded
+        frame_system::Pallet::<Test>::set_block_number(16);
+        assert_err!(VotingPallet::reveal_vote(election, 3, alt_vote(&[2]), salt));
+        VotingPallet::on_finalize(15);
+        assert!(VotingPallet::active_votings(election).is_none());
+        let result = VotingPallet::get_last_election_result(election).unwrap();
+        assert_eq!(
+            result.count.winners.keys().next(),
+            Some(&[1_u8; 32].to_vec())
+        );
+    });
+}
+
+#[test]
+fn ballot_validation_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let subject = Hashing::hash(&[1; 32]);
+        let error = |result: Result<(), Error<Test>>| result.map_err(DispatchError::from);
+
+        assert_ok!(VotingPallet::create_alt_voting(
+            subject,
+            10,
+            candidates(&[1, 2, 3]),
+            1,
+            CountingMethod::Score,
+            VotingRules::open(),
+            None
+        ));
+        assert_eq!(
+            error(VotingPallet::alt_vote(subject, 1, alt_vote(&[1, 4]), 1)),
+            Err(<Error<Test>>::UnknownCandidate.into())
+        );
+        assert_eq!(
+            error(VotingPallet::alt_vote(subject, 1, alt_vote(&[1, 2, 1]), 1)),
+            Err(<Error<Test>>::DuplicateCandidate.into())
+        );
+        let scores = vec![1; MaxPreferences::get() as usize + 1];
+        assert_eq!(
+            error(VotingPallet::alt_vote(
+                subject,
+                1,
+                AltVote::with_scores(alt_vote(&[1]).content, scores),
+                1
+            )),
+            Err(<Error<Test>>::TooManyPreferences.into())
+        );
+
+        // the ballot of a voter is replaced by the next one
+        assert_ok!(VotingPallet::alt_vote(
+            subject,
+            1,
+            AltVote::with_scores(alt_vote(&[1, 2]).content, vec![2, 7]),
+            1
+        ));
+        assert_ok!(VotingPallet::alt_vote(
+            subject,
+            1,
+            AltVote::with_scores(alt_vote(&[1, 2]).content, vec![9, 3]),
+            1
+        ));
+        assert_eq!(
+            VotingPallet::calculate_alt_vote_winner(subject).unwrap(),
+            [1_u8; 32].to_vec()
+        );
+    });
+}
+
+#[test]
+fn long_candidate_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let subject = Hashing::hash(&[1; 32]);
+        let long_candidate = vec![1; MaxCandidateLength::get() as usize + 1];
+
+        assert_ok!(VotingPallet::create_alt_voting(
+            subject,
+            10,
+            vec![long_candidate.clone()].into_iter().collect(),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            None
+        ));
+        assert_eq!(
+            VotingPallet::alt_vote(subject, 1, AltVote::new(vec![long_candidate].into()), 1)
+                .map_err(DispatchError::from),
+            Err(<Error<Test>>::CandidateTooLong.into())
+        );
+    });
+}
+
+#[test]
+fn ballots_view_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let election = Hashing::hash(&[1; 32]);
+        let other_election = Hashing::hash(&[2; 32]);
+
+        for subject in [election, other_election] {
+            assert_ok!(VotingPallet::create_alt_voting(
+                subject,
+                10,
+                candidates(&[1, 2]),
+                1,
+                CountingMethod::Plurality,
+                VotingRules::open(),
+                None
+            ));
+        }
+        for voter in 0..20 {
+            assert_ok!(VotingPallet::alt_vote(election, voter, alt_vote(&[1]), 1));
+        }
+        // the voters can change their ballots, they are counted once
+        assert_ok!(VotingPallet::alt_vote(election, 0, alt_vote(&[2]), 1));
+        assert_ok!(VotingPallet::alt_vote(
+            other_election,
+            100,
+            alt_vote(&[2]),
+            3
+        ));
+
+        // the view reads only the ballots of its voting
+        let ballots: Vec<_> = BallotsView::<Test>::new(other_election, 0).iter().collect();
+        assert_eq!(ballots, vec![(100, (alt_vote(&[2]), 3))]);
+        assert_eq!(BallotsView::<Test>::new(election, 0).iter().count(), 20);
+
+        // the finalizations weigh the ballots cast
+        assert_eq!(
+            VotingPallet::on_initialize(10),
+            4 + 2 * 10
+                + VotingPallet::finalization_weight(20)
+                + VotingPallet::finalization_weight(1)
+        );
+        VotingPallet::on_finalize(10);
+
+        // the ballots are removed a page in a block,
+        // a new voting with the subject does not see them
+        frame_system::Pallet::<Test>::set_block_number(11);
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            10,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            None
+        ));
+        assert_eq!(BallotsView::<Test>::new(election, 11).iter().count(), 0);
+        let remaining = || {
+            BallotsView::<Test>::new(election, 0).iter().count()
+                + BallotsView::<Test>::new(other_election, 0).iter().count()
+        };
+        assert_eq!(remaining(), 21);
+        for block in 11..14 {
+            VotingPallet::on_initialize(block);
+        }
+        assert!(remaining() > 0);
+        VotingPallet::on_initialize(14);
+        assert_eq!(remaining(), 0);
+
+        // a voting created and closed in the block keeps the subject
+        // until its ballots are removed
+        frame_system::Pallet::<Test>::set_block_number(30);
+        let create = || {
+            VotingPallet::create_alt_voting(
+                other_election,
+                10,
+                candidates(&[1, 2]),
+                1,
+                CountingMethod::Plurality,
+                VotingRules::all_voters(1),
+                None,
+            )
+            .map_err(DispatchError::from)
+        };
+        assert_ok!(create());
+        assert_ok!(VotingPallet::alt_vote(other_election, 1, alt_vote(&[1]), 1));
+        VotingPallet::on_finalize(30);
+        assert_eq!(create(), Err(<Error<Test>>::BallotsAreBeingRemoved.into()));
+    });
+}
+
+#[test]
+fn scheduled_finalization_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let closing: Vec<_> = (1..4).map(|i| Hashing::hash(&[i; 32])).collect();
+        let later = Hashing::hash(&[4; 32]);
+        for subject in closing.iter() {
+            assert_ok!(VotingPallet::create_voting(
+                *subject,
+                10,
+                VotingRules::open(),
+                None
+            ));
+        }
+        assert_ok!(VotingPallet::create_voting(
+            later,
+            20,
+            VotingRules::open(),
+            None
+        ));
+
+        // a voting without ballots reads 4 entries and writes 7 entries
+        let finalization_weight = 4 + 7 * 10;
+        assert_eq!(VotingPallet::on_initialize(5), 3);
+        assert_eq!(
+            VotingPallet::on_initialize(10),
+            4 + 2 * 10 + MaxFinalizationsPerBlock::get() as u64 * finalization_weight
+        );
+
+        // only two votings are finalized in a block, the third one is scheduled to the next
+        VotingPallet::on_finalize(10);
+        let deferred: Vec<_> = closing
+            .iter()
+            .filter(|subject| VotingPallet::active_votings(*subject).is_some())
+            .collect();
+        assert_eq!(deferred.len(), 1);
+        assert!(VotingPallet::active_votings(later).is_some());
+
+        // the deferred voting does not take the votes after its end
+        frame_system::Pallet::<Test>::set_block_number(11);
+        assert_eq!(
+            VotingPallet::vote(*deferred[0], 1).map_err(DispatchError::from),
+            Err(<Error<Test>>::VotingPeriodIsOver.into())
+        );
+        assert_eq!(
+            VotingPallet::on_initialize(11),
+            4 + 2 * 10 + finalization_weight
+        );
+        VotingPallet::on_finalize(11);
+        assert!(VotingPallet::active_votings(*deferred[0]).is_none());
+        assert!(VotingPallet::active_votings(later).is_some());
+
+        // the skipped blocks are caught up by `MaxFinalizationsPerBlock` blocks at a time,
+        // the current block is not delayed by them
+        assert_ok!(VotingPallet::create_voting(
+            closing[0],
+            5,
+            VotingRules::open(),
+            None
+        ));
+        VotingPallet::on_finalize(20);
+        assert!(VotingPallet::active_votings(later).is_none());
+        assert!(VotingPallet::active_votings(closing[0]).is_some());
+        VotingPallet::on_finalize(21);
+        assert!(VotingPallet::active_votings(closing[0]).is_some());
+        VotingPallet::on_finalize(22);
+        assert!(VotingPallet::active_votings(closing[0]).is_none());
+    });
+}
+
+#[test]
+fn early_close_schedule_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let subjects: Vec<_> = (1..4).map(|i| Hashing::hash(&[i; 32])).collect();
+        let finalization_weight = 4 + 7 * 10;
+        let block_weight = || frame_system::Pallet::<Test>::block_weight().total();
+        let active = || {
+            subjects
+                .iter()
+                .filter(|subject| VotingPallet::active_votings(*subject).is_some())
+                .count()
+        };
+        for subject in subjects.iter() {
+            assert_ok!(VotingPallet::create_voting(
+                *subject,
+                10,
+                VotingRules::all_voters(1),
+                None
+            ));
+        }
+
+        // the votings closed in a block with room are finalized in it with the extra weight
+        frame_system::Pallet::<Test>::set_block_number(5);
+        assert_eq!(VotingPallet::on_initialize(5), 3);
+        let weight = block_weight();
+        assert_ok!(VotingPallet::vote(subjects[0], 1));
+        assert_eq!(block_weight(), weight + finalization_weight);
+        assert_ok!(VotingPallet::vote(subjects[1], 1));
+        assert_eq!(block_weight(), weight + 2 * finalization_weight);
+        // the block is full, the voting is deferred without the extra weight
+        assert_ok!(VotingPallet::vote(subjects[2], 1));
+        assert_eq!(block_weight(), weight + 2 * finalization_weight);
+        VotingPallet::on_finalize(5);
+        assert_eq!(active(), 1);
+
+        // the deferred voting is finalized in the next block
+        assert_eq!(VotingPallet::on_initialize(6), 3 + finalization_weight);
+        VotingPallet::on_finalize(6);
+        assert_eq!(active(), 0);
+        assert_eq!(VotingPallet::on_initialize(10), 4 + 2 * 10);
+    });
+}
+
+#[test]
+fn finalization_budget_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let heavy = Hashing::hash(&[1; 32]);
+        let light = Hashing::hash(&[2; 32]);
+        assert_ok!(VotingPallet::create_alt_voting(
+            heavy,
+            10,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            None
+        ));
+        assert_ok!(VotingPallet::create_voting(
+            light,
+            10,
+            VotingRules::open(),
+            None
+        ));
+        for voter in 0..200 {
+            assert_ok!(VotingPallet::alt_vote(heavy, voter, alt_vote(&[1]), 1));
+        }
+
+        // the heavy voting takes the budget of the block, the light one waits for the next
+        let heavy_weight = VotingPallet::finalization_weight(200);
+        let light_weight = VotingPallet::finalization_weight(0);
+        assert!(heavy_weight + light_weight > VotingPallet::finalizations_budget());
+        assert_eq!(VotingPallet::on_initialize(10), 4 + 2 * 10 + heavy_weight);
+        VotingPallet::on_finalize(10);
+        assert!(VotingPallet::active_votings(heavy).is_none());
+        assert!(VotingPallet::active_votings(light).is_some());
+
+        // the first page of the heavy ballots is removed
+        assert_eq!(
+            VotingPallet::on_initialize(11),
+            3 + 2 * 10 + VotingPallet::ballot_removal_weight() + light_weight
+        );
+        VotingPallet::on_finalize(11);
+        assert!(VotingPallet::active_votings(light).is_none());
+    });
+}
+
+#[test]
+fn full_schedule_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let subjects: Vec<_> = (1..6).map(|i| Hashing::hash(&[i; 32])).collect();
+        for subject in subjects[..4].iter() {
+            assert_ok!(VotingPallet::create_voting(
+                *subject,
+                10,
+                VotingRules::open(),
+                None
+            ));
+        }
+
+        // the blocks from the end are probed `MaxFinalizationsPerBlock` times
+        assert_eq!(
+            VotingPallet::create_voting(subjects[4], 10, VotingRules::open(), None)
+                .map_err(DispatchError::from),
+            Err(<Error<Test>>::ScheduleIsFull.into())
+        );
+        assert!(VotingPallet::active_votings(subjects[4]).is_none());
+        assert_ok!(VotingPallet::create_voting(
+            subjects[4],
+            12,
+            VotingRules::open(),
+            None
+        ));
+    });
+}
+
+#[test]
+fn quorum_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let election = Hashing::hash(&[1; 32]);
+
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            10,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::all_voters(4).with_quorum(3),
+            None
+        ));
+        assert_ok!(VotingPallet::alt_vote(election, 1, alt_vote(&[1]), 1));
+        assert_ok!(VotingPallet::alt_vote(election, 2, alt_vote(&[1]), 1));
+        // a changed ballot is not counted twice
+        assert_ok!(VotingPallet::alt_vote(election, 2, alt_vote(&[2]), 1));
+        assert_eq!(VotingPallet::active_votings(election).unwrap().voted, 2);
+        VotingPallet::on_finalize(10);
+
+        // the voting without the quorum has no winners
+        let result = VotingPallet::get_last_election_result(election).unwrap();
+        assert_eq!(result.count, CountResult::default());
+        assert_eq!(take_finalized(), vec![election]);
+    });
+}
+
+#[test]
+fn voting_events_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let motion = Hashing::hash(&[1; 32]);
+        let election = Hashing::hash(&[2; 32]);
+        // the events are not deposited in the genesis block
+        frame_system::Pallet::<Test>::set_block_number(1);
+
+        assert_ok!(VotingPallet::create_voting(
+            motion,
+            10,
+            VotingRules::all_voters(2).with_quorum(2),
+            None
+        ));
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            10,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            None
+        ));
+        assert_ok!(VotingPallet::vote(motion, 3));
+        assert_ok!(VotingPallet::alt_vote(election, 1, alt_vote(&[2]), 1));
+        VotingPallet::on_finalize(11);
+
+        let events: Vec<_> = frame_system::Pallet::<Test>::events()
+            .into_iter()
+            .map(|record| record.event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                mock::Event::pallet_voting(crate::Event::VotingCreated(motion, 11)),
+                mock::Event::pallet_voting(crate::Event::VotingCreated(election, 11)),
+                mock::Event::pallet_voting(crate::Event::VoteCast(motion)),
+                mock::Event::pallet_voting(crate::Event::VoteCast(election)),
+                mock::Event::pallet_voting(crate::Event::VotingFinalized(motion, 3, false)),
+                mock::Event::pallet_voting(crate::Event::AltVotingFinalized(
+                    election,
+                    vec![[2; 32].to_vec()]
+                )),
+            ]
+        );
+    });
+}
+
+#[test]
+fn active_votings_api_test() {
+    new_test_ext().execute_with(|| {
+        type Hashing = <Test as frame_system::Config>::Hashing;
+        let motion = Hashing::hash(&[1; 32]);
+        let election = Hashing::hash(&[2; 32]);
+
+        assert_ok!(VotingPallet::create_voting(
+            motion,
+            10,
+            VotingRules::open(),
+            None
+        ));
+        assert_ok!(VotingPallet::create_alt_voting(
+            election,
+            20,
+            candidates(&[1, 2]),
+            1,
+            CountingMethod::Plurality,
+            VotingRules::open(),
+            None
+        ));
+        assert_ok!(VotingPallet::vote(motion, 3));
+        assert_ok!(VotingPallet::alt_vote(election, 1, alt_vote(&[2]), 1));
+        frame_system::Pallet::<Test>::set_block_number(4);
+
+        let votings = VotingPallet::get_active_votings();
+        assert_eq!(votings.len(), 2);
+        assert_eq!(votings[&motion].settings.result, 3);
+        assert_eq!(votings[&motion].tally, CountResult::default());
+        assert_eq!((votings[&motion].end, votings[&motion].remaining), (10, 6));
+
+        // the ballots of an election are counted as if it ended now
+        let info = VotingPallet::get_active_voting(election).unwrap();
+        assert_eq!(info.settings.candidates, candidates(&[1, 2]));
+        assert_eq!(info.tally.winners.keys().next(), Some(&[2_u8; 32].to_vec()));
+        assert_eq!((info.end, info.remaining), (20, 16));
+
+        VotingPallet::on_finalize(10);
+        assert!(VotingPallet::get_active_voting(motion).is_none());
+        assert_eq!(VotingPallet::get_active_votings().len(), 1);
+    });
+}
+
+#[test]
+fn delegation_test() {
+    new_test_ext().execute_with(|| {
+        let topic = DelegationTopic::Referendums;
+        let error = |result: Result<(), Error<Test>>| result.map_err(DispatchError::from);
+        frame_system::Pallet::<Test>::set_block_number(1);
+
+        assert_eq!(
+            error(VotingPallet::delegate(topic, 1, 1)),
+            Err(<Error<Test>>::SelfDelegation.into())
+        );
+        // 1 -> 2 -> 3, 4 -> 3
+        assert_ok!(VotingPallet::delegate(topic, 1, 2));
+        assert_ok!(VotingPallet::delegate(topic, 2, 3));
+        assert_ok!(VotingPallet::delegate(topic, 4, 3));
+        assert_eq!(
+            error(VotingPallet::delegate(topic, 3, 1)),
+            Err(<Error<Test>>::DelegationCycle.into())
+        );
+        // 3 represents `MaxDelegators` voters
+        assert_eq!(VotingPallet::represented(topic, 3), MaxDelegators::get());
+        assert_eq!(VotingPallet::represented(topic, 2), 1);
+        assert_eq!(
+            error(VotingPallet::delegate(topic, 5, 3)),
+            Err(<Error<Test>>::TooManyDelegators.into())
+        );
+        assert_eq!(
+            error(VotingPallet::delegate(topic, 5, 2)),
+            Err(<Error<Test>>::TooManyDelegators.into())
+        );
+        // the topics are independent
+        assert_ok!(VotingPallet::delegate(
+            DelegationTopic::AssemblyElections,
+            3,
+            1
+        ));
+
+        let mut voters = VotingPallet::delegated_voters(topic, &3, |_| false);
+        voters.sort();
+        assert_eq!(voters, vec![1, 2, 4]);
+        // 2 votes for 1, so 3 votes only for 4
+        assert_eq!(
+            VotingPallet::delegated_voters(topic, &3, |voter| *voter == 2),
+            vec![4]
+        );
+
+        // the delegation moves within the chain of 3
+        assert_ok!(VotingPallet::delegate(topic, 1, 3));
+        assert_eq!(VotingPallet::represented(topic, 2), 0);
+        assert_eq!(VotingPallet::represented(topic, 3), MaxDelegators::get());
+
+        // the new delegation replaces the old one
+        assert_ok!(VotingPallet::delegate(topic, 1, 4));
+        assert_eq!(VotingPallet::represented(topic, 4), 1);
+        assert_eq!(VotingPallet::delegations(topic, 1), Some(4));
+        assert_eq!(VotingPallet::delegated_voters(topic, &2, |_| false), vec![]);
+        assert_ok!(VotingPallet::undelegate(topic, 4));
+        assert_eq!(VotingPallet::represented(topic, 3), 1);
+        assert_eq!(
+            VotingPallet::delegated_voters(topic, &3, |_| false),
+            vec![2]
+        );
+        assert_eq!(
+            error(VotingPallet::undelegate(topic, 4)),
+            Err(<Error<Test>>::DelegationNotFound.into())
+        );
+
+        let events: Vec<_> = frame_system::Pallet::<Test>::events()
+            .into_iter()
+            .map(|record| record.event)
+            .collect();
+        assert_eq!(
+            events.last(),
+            Some(&mock::Event::pallet_voting(crate::Event::Undelegated(
+                topic, 4
+            )))
+        );
+        assert_eq!(
+            events[0],
+            mock::Event::pallet_voting(crate::Event::Delegated(topic, 1, 2))
+        );
+    });
+}
+
+proptest! {
+    #[test]
+    fn early_close_test(voters_number in 1_u32..8, votes in 0_u32..16) {
+        new_test_ext().execute_with(|| {
+            type Hashing = <Test as frame_system::Config>::Hashing;
+            let subject = Hashing::hash(&[1; 32]);
+            frame_system::Pallet::<Test>::set_block_number(1);
+            assert_ok!(VotingPallet::create_voting(
+                subject,
+                10,
+                VotingRules::all_voters(voters_number),
+                None
+            ));
+
+            for i in 0..votes {
+                let voted = VotingPallet::vote(subject, 1).map_err(DispatchError::from);
+                if i < voters_number {
+                    prop_assert!(voted.is_ok());
+                } else {
+                    prop_assert_eq!(voted, Err(<Error<Test>>::AllVotersVoted.into()));
+                }
+            }
+            let settings = VotingPallet::active_votings(subject).unwrap();
+            prop_assert_eq!(settings.voted, votes.min(voters_number));
+
+            // the voting closes in the block the last voter voted in
+            VotingPallet::on_finalize(1);
+            prop_assert_eq!(
+                VotingPallet::active_votings(subject).is_none(),
+                votes >= voters_number
+            );
+
+            // and it is finalized exactly once
+            VotingPallet::on_finalize(11);
+            prop_assert!(VotingPallet::active_votings(subject).is_none());
+            prop_assert_eq!(take_finalized(), vec![subject]);
+            Ok(())
+        })?;
+    }
+
+    #[test]
+    fn quorum_and_turnout_test(
+        voters_number in 1_u32..20,
+        quorum in 0_u32..20,
+        turnout in 0_u8..=100,
+        votes in 0_u32..20,
+    ) {
+        new_test_ext().execute_with(|| {
+            type Hashing = <Test as frame_system::Config>::Hashing;
+            let subject = Hashing::hash(&[1; 32]);
+            let votes = votes.min(voters_number);
+            let rules = VotingRules {
+                voters_number,
+                quorum,
+                turnout: Percent::from_percent(turnout),
+                early_close: false,
+            };
+            assert_ok!(VotingPallet::create_voting(subject, 10, rules, None));
+            for _ in 0..votes {
+                assert_ok!(VotingPallet::vote(subject, 1));
+            }
+
+            let settings = VotingPallet::active_votings(subject).unwrap();
+            prop_assert_eq!(
+                settings.quorum_reached(),
+                votes >= quorum && votes * 100 >= turnout as u32 * voters_number
+            );
+            Ok(())
+        })?;
+    }
+
+    #[test]
+    fn open_voting_test(votes in 0_u32..32) {
+        new_test_ext().execute_with(|| {
+            type Hashing = <Test as frame_system::Config>::Hashing;
+            let subject = Hashing::hash(&[1; 32]);
+            assert_ok!(VotingPallet::create_voting(subject, 10, VotingRules::open(), None));
+            for _ in 0..votes {
+                assert_ok!(VotingPallet::vote(subject, 1));
+            }
+
+            // the voting without the known voters is never closed early
+            VotingPallet::on_finalize(9);
+            prop_assert!(VotingPallet::active_votings(subject).is_some());
+            prop_assert!(VotingPallet::active_votings(subject).unwrap().quorum_reached());
+            VotingPallet::on_finalize(10);
+            prop_assert_eq!(take_finalized(), vec![subject]);
+            Ok(())
+        })?;
+    }
+}
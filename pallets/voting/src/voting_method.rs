@@ -0,0 +1,210 @@
+// Voting methods available to the votings of the registry.
+//
+// Every method counts the same ballots, a ranked list of candidates (`AltVote::content`) with
+// the voting power of the voter, and elects `winners_amount` candidates. The ranked methods
+// transfer the ballots between the candidates (see `counting`), the other methods give points
+// to the candidates in a single pass and elect the candidates with the most points.
+//
+// A new method is added with a `VotingMethod` implementation and a `CountingMethod` variant,
+// the pallets creating votings only pick the variant.
+use crate::counting::{self, CountAction, CountResult, CountRound, Tallies, TieBreakSeed};
+use crate::{AltVote, Candidate};
+use frame_support::codec::{Decode, Encode};
+use sp_std::collections::btree_set::BTreeSet;
+
+// The highest score a `Score` ballot can give to a candidate, greater scores are capped.
+pub const MAX_SCORE: u32 = 10;
+
+pub trait VotingMethod {
+    fn count(
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult;
+}
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum CountingMethod {
+    // the first preference of every ballot gets its power
+    Plurality,
+    // every candidate named by a ballot gets its power
+    Approval,
+    // the last placed candidates are eliminated until only the winners are left
+    InstantRunoff,
+    // single transferable vote with the Droop quota and Gregory surplus transfers
+    SingleTransferableVote,
+    // the preference at position `i` gets `candidates - 1 - i` points for every unit of power
+    Borda,
+    // every candidate named by a ballot gets its score for every unit of power
+    Score,
+}
+
+impl CountingMethod {
+    pub fn count(
+        &self,
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult {
+        match self {
+            Self::Plurality => Plurality::count(candidates, winners_amount, ballots, seed),
+            Self::Approval => Approval::count(candidates, winners_amount, ballots, seed),
+            Self::InstantRunoff => InstantRunoff::count(candidates, winners_amount, ballots, seed),
+            Self::SingleTransferableVote => {
+                SingleTransferableVote::count(candidates, winners_amount, ballots, seed)
+            }
+            Self::Borda => Borda::count(candidates, winners_amount, ballots, seed),
+            Self::Score => Score::count(candidates, winners_amount, ballots, seed),
+        }
+    }
+}
+
+pub struct Plurality;
+
+impl VotingMethod for Plurality {
+    fn count(
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult {
+        elect_highest(counting::tally(candidates, ballots), winners_amount, seed)
+    }
+}
+
+pub struct Approval;
+
+impl VotingMethod for Approval {
+    fn count(
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult {
+        let points = award_points(candidates, ballots, |_, _, _, power| power);
+        elect_highest(points, winners_amount, seed)
+    }
+}
+
+pub struct InstantRunoff;
+
+impl VotingMethod for InstantRunoff {
+    fn count(
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult {
+        counting::eliminate_to(candidates, winners_amount, ballots, seed)
+    }
+}
+
+pub struct SingleTransferableVote;
+
+impl VotingMethod for SingleTransferableVote {
+    fn count(
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult {
+        counting::single_transferable_vote(candidates, winners_amount, ballots, seed)
+    }
+}
+
+pub struct Borda;
+
+impl VotingMethod for Borda {
+    fn count(
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult {
+        let positions = candidates.len() as u64;
+        let points = award_points(candidates, ballots, |_, _, rank, power| {
+            positions
+                .saturating_sub(1)
+                .saturating_sub(rank as u64)
+                .saturating_mul(power)
+        });
+        elect_highest(points, winners_amount, seed)
+    }
+}
+
+pub struct Score;
+
+impl VotingMethod for Score {
+    fn count(
+        candidates: &BTreeSet<Candidate>,
+        winners_amount: u32,
+        ballots: &[(AltVote, u64)],
+        seed: &TieBreakSeed,
+    ) -> CountResult {
+        let points = award_points(candidates, ballots, |ballot, index, _, power| {
+            let score = ballot.scores.get(index).copied().unwrap_or_default();
+            (score.min(MAX_SCORE) as u64).saturating_mul(power)
+        });
+        elect_highest(points, winners_amount, seed)
+    }
+}
+
+// Gives every candidate the points of the ballots naming it. `points` gets the ballot, the
+// index of the preference in the ballot, the rank of the candidate among the valid preferences
+// and the ballot power. Unknown and repeated preferences are skipped and do not take a rank.
+fn award_points<F>(
+    candidates: &BTreeSet<Candidate>,
+    ballots: &[(AltVote, u64)],
+    points: F,
+) -> Tallies
+where
+    F: Fn(&AltVote, usize, usize, u64) -> u64,
+{
+    let mut tallies: Tallies = candidates
+        .iter()
+        .map(|candidate| (candidate.clone(), 0))
+        .collect();
+    ballots.iter().for_each(|(ballot, power)| {
+        let mut named: BTreeSet<&Candidate> = BTreeSet::new();
+        let mut rank = 0;
+        ballot
+            .content
+            .iter()
+            .enumerate()
+            .for_each(|(index, candidate)| {
+                if !named.insert(candidate) {
+                    return;
+                }
+                if let Some(votes) = tallies.get_mut(candidate) {
+                    *votes = votes.saturating_add(points(ballot, index, rank, *power));
+                    rank += 1;
+                }
+            });
+    });
+    tallies
+}
+
+// Elects the candidates with the most points one by one, ties are broken by `first_placed`.
+// Every round keeps the points of the candidates not elected yet.
+fn elect_highest(mut points: Tallies, winners_amount: u32, seed: &TieBreakSeed) -> CountResult {
+    let mut result = CountResult::default();
+    while (result.winners.len() as u32) < winners_amount {
+        let elected = match counting::first_placed(&points, &[], seed) {
+            Some(elected) => elected,
+            None => break,
+        };
+        let votes = points.get(&elected).copied().unwrap_or_default();
+        result.rounds.push(CountRound {
+            tallies: points.clone(),
+            action: CountAction::Elected(elected.clone()),
+            transfers: Tallies::new(),
+            exhausted: 0,
+        });
+        points.remove(&elected);
+        result.winners.insert(elected, votes);
+    }
+    result
+}
@@ -44,9 +44,10 @@ pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::CurrencyAdapter;
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{transaction_validity::TransactionPriority, Perbill, Permill};
+pub use sp_runtime::{transaction_validity::TransactionPriority, Perbill, Percent, Permill};
 
 pub use pallet_assembly;
+use pallet_assembly::VotingPower;
 /// Import the Liberland pallets.
 pub use pallet_documentation;
 pub use pallet_identity;
@@ -56,6 +57,7 @@ pub use pallet_prime_minister;
 pub use pallet_referendum;
 pub use pallet_staking;
 pub use pallet_voting;
+use pallet_voting::CountingMethod;
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -117,7 +119,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
     //   the compatible custom types.
-    spec_version: 100,
+    spec_version: 101,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 1,
@@ -311,17 +313,53 @@ impl pallet_min_interior::Config for Runtime {
     type RequestBlockNummber = RequestBlockNummber;
     type IdentityTrait = IdentityPallet;
 }
+
+parameter_types! {
+    pub const MaxPreferences: u32 = 64;
+    // passport ids are 32 bytes long
+    pub const MaxCandidateLength: u32 = 32;
+    pub const MaxFinalizationsPerBlock: u32 = 16;
+    pub const MaxBallotRemovalsPerBlock: u32 = 1_000;
+    pub const MaxDelegators: u32 = 1024;
+}
+
 /// Configure the pallet-voting in pallets/voting.
 impl pallet_voting::Config for Runtime {
-    type FinalizeVotingDispatch = (ReferendumPallet, AssemblyPallet);
-    type FinalizeAltVotingDispatch = PrimeMinPallet;
-    type FinalizeAltVotingListDispatch = AssemblyPallet;
+    type Event = Event;
+    type FinalizeVotingDispatch = (ReferendumPallet, AssemblyPallet, PrimeMinPallet);
+    type MaxPreferences = MaxPreferences;
+    type MaxCandidateLength = MaxCandidateLength;
+    type MaxFinalizationsPerBlock = MaxFinalizationsPerBlock;
+    type MaxBallotRemovalsPerBlock = MaxBallotRemovalsPerBlock;
+    type MaxDelegators = MaxDelegators;
 }
 parameter_types! {
     // 72 hours
     pub const PetitionDuration: u32 = 72 * 60 * 60 * 1000 / MILLISECS_PER_BLOCK as BlockNumber;
     // 72 hours
     pub const ReferendumDuration: u32 = 72 * 60 * 60 * 1000 / MILLISECS_PER_BLOCK as BlockNumber;
+    pub const RevealDuration: Option<BlockNumber> = None;
+    // 24 hours
+    pub const EnactmentDelay: BlockNumber = 24 * 60 * 60 * 1000 / MILLISECS_PER_BLOCK as BlockNumber;
+    pub const PetitionDeposit: Balance = DOLLARS;
+    pub const PreimageByteDeposit: Balance = CENTS;
+    // 24 hours
+    pub const PetitionCooldown: BlockNumber = 24 * 60 * 60 * 1000 / MILLISECS_PER_BLOCK as BlockNumber;
+    pub const MaxSuggestionLength: u32 = 16 * 1024;
+    pub const MaxEnactmentsPerBlock: u32 = 16;
+    pub const ConstitutionalRules: pallet_referendum::ReferendumRules =
+        pallet_referendum::ReferendumRules {
+            quorum: Percent::from_percent(50),
+            approval: Percent::from_percent(66),
+        };
+    pub const FiscalRules: pallet_referendum::ReferendumRules = pallet_referendum::ReferendumRules {
+        quorum: Percent::from_percent(30),
+        approval: Percent::from_percent(50),
+    };
+    pub const OtherRules: pallet_referendum::ReferendumRules = pallet_referendum::ReferendumRules {
+        quorum: Percent::from_percent(10),
+        approval: Percent::from_percent(50),
+    };
 }
 /// Configure the pallet-referendum in pallets/referendum.
 impl pallet_referendum::Config for Runtime {
@@ -329,8 +367,24 @@ impl pallet_referendum::Config for Runtime {
     type PetitionDuration = PetitionDuration;
     // 72 hours
     type ReferendumDuration = ReferendumDuration;
+    type RevealDuration = RevealDuration;
+    type ConstitutionalRules = ConstitutionalRules;
+    type FiscalRules = FiscalRules;
+    type OtherRules = OtherRules;
+    // 24 hours
+    type EnactmentDelay = EnactmentDelay;
+    type MaxEnactmentsPerBlock = MaxEnactmentsPerBlock;
+    type Origin = Origin;
+    type Proposal = Call;
+    type Currency = Balances;
+    type PetitionDeposit = PetitionDeposit;
+    type PreimageByteDeposit = PreimageByteDeposit;
+    // 24 hours
+    type PetitionCooldown = PetitionCooldown;
+    type MaxSuggestionLength = MaxSuggestionLength;
     type IdentityTrait = IdentityPallet;
     type VotingTrait = VotingPallet;
+    type Laws = AssemblyPallet;
 }
 
 /// Configure the pallet-documentation in pallets/documentation.
@@ -350,15 +404,22 @@ parameter_types! {
     pub const LawVotingDuration: u32 = 60 * 1000 / MILLISECS_PER_BLOCK as BlockNumber;
     pub const AssemblyVotingHash: H256 = sp_core::H256::zero();
     pub const WinnersAmount: u32 = 3;
+    pub const AssemblyCountingMethod: CountingMethod = CountingMethod::SingleTransferableVote;
+    pub const AssemblyVotingPower: VotingPower = VotingPower::Linear;
+    pub const AssemblyRevealDuration: Option<BlockNumber> = None;
     pub const PrimeMinVotingDuration: u32 = 2 * 60 * 1000 / 6000;
     pub const PrimeMinVotingHash: H256 = sp_core::H256::repeat_byte(1);
     pub const PrimeMinVotingDelay: u32 = 10;
+    pub const CandidateDeposit: Balance = 10 * DOLLARS;
+    pub const CandidateDepositThreshold: Percent = Percent::from_percent(5);
+    pub const MaxDisplayNameLength: u32 = 64;
 
 
 }
 
 /// Configure the pallet-documentation in pallets/assembly.
 impl pallet_assembly::Config for Runtime {
+    type Event = Event;
     // // 1 week
     // const ASSEMBLY_ELECTION_PERIOD: BlockNumber =
     //     (24 * 7 * 60 * 60 * 1000 / MILLISECS_PER_BLOCK as BlockNumber);
@@ -380,6 +441,9 @@ impl pallet_assembly::Config for Runtime {
 
     type AssemblyVotingHash = AssemblyVotingHash;
     type WinnersAmount = WinnersAmount;
+    type AssemblyCountingMethod = AssemblyCountingMethod;
+    type AssemblyVotingPower = AssemblyVotingPower;
+    type AssemblyRevealDuration = AssemblyRevealDuration;
 
     type IdentTrait = IdentityPallet;
     type VotingTrait = VotingPallet;
@@ -387,6 +451,12 @@ impl pallet_assembly::Config for Runtime {
     type PrimeMinVotingDuration = PrimeMinVotingDuration;
     type PrimeMinVotingHash = PrimeMinVotingHash;
     type PrimeMinVotingDelay = PrimeMinVotingDelay;
+    type DepositCurrency = Balances;
+    type CandidateDeposit = CandidateDeposit;
+    type CandidateDepositThreshold = CandidateDepositThreshold;
+    type MaxDisplayNameLength = MaxDisplayNameLength;
+    // the citizens override the assembly by a binding referendum
+    type ReferendumOrigin = pallet_referendum::EnsureReferendum;
 }
 
 parameter_types! {
@@ -520,6 +590,7 @@ impl pallet_staking::Config for Runtime {
     type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
     type NextNewSession = Session;
     type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
+    type OnLiberStakeChanged = AssemblyPallet;
 }
 
 impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
@@ -550,12 +621,12 @@ construct_runtime!(
         // Liberland pallets
         IdentityPallet: pallet_identity::{Pallet, Call, Config<T>, Storage},
         MinInteriorPallet: pallet_min_interior::{Pallet, Call, Storage},
-        VotingPallet: pallet_voting::{Pallet, Call, Storage},
-        ReferendumPallet: pallet_referendum::{Pallet, Call, Storage},
+        VotingPallet: pallet_voting::{Pallet, Call, Storage, Event<T>},
+        ReferendumPallet: pallet_referendum::{Pallet, Call, Storage, Origin},
         DocumentationPallet: pallet_documentation::{Pallet, Call, Storage},
         PrimeMinPallet: pallet_prime_minister::{Pallet, Call, Storage},
         StakingPallet: pallet_staking::{Pallet, Call, Storage, Config<T>, Event<T>},
-        AssemblyPallet: pallet_assembly::{Pallet, Call, Storage},
+        AssemblyPallet: pallet_assembly::{Pallet, Call, Storage, Event<T>},
     }
 );
 
@@ -741,17 +812,85 @@ impl_runtime_apis! {
     }
 
     impl pallet_referendum::ReferendumPalletApi<Block, Runtime> for Runtime {
-        fn get_active_petitions() -> BTreeMap<Hash, pallet_referendum::Suggestion> {
+        fn get_active_petitions() -> BTreeMap<Hash, pallet_referendum::Suggestion<Hash>> {
             ReferendumPallet::get_active_petitions()
         }
 
-        fn get_active_referendums() -> BTreeMap<Hash, pallet_referendum::Suggestion> {
+        fn get_active_referendums() -> BTreeMap<Hash, pallet_referendum::Suggestion<Hash>> {
             ReferendumPallet::get_active_referendums()
         }
 
-        fn get_successfull_referendums() -> BTreeMap<Hash, pallet_referendum::Suggestion> {
+        fn get_successfull_referendums() -> BTreeMap<Hash, pallet_referendum::Suggestion<Hash>> {
             ReferendumPallet::get_successfull_referendums()
         }
+
+        fn get_suggestion(subject: Hash) -> Option<pallet_referendum::Suggestion<Hash>> {
+            ReferendumPallet::get_suggestion(subject)
+        }
+
+        fn get_closed_suggestions_count() -> u32 {
+            ReferendumPallet::get_closed_suggestions_count()
+        }
+
+        fn get_closed_suggestions(
+            start: u32,
+            count: u32,
+        ) -> Vec<pallet_referendum::ClosedSuggestion<Hash, BlockNumber>> {
+            ReferendumPallet::get_closed_suggestions(start, count)
+        }
+    }
+
+    impl pallet_assembly::AssemblyPalletApi<Block, Runtime> for Runtime {
+        fn get_candidates() -> Vec<(
+            pallet_voting::Candidate,
+            Option<pallet_assembly::CandidateProfile<AccountId, Balance, Hash>>,
+        )> {
+            AssemblyPallet::get_candidates()
+        }
+
+        fn get_election_calendar() -> Option<pallet_assembly::ElectionCalendar<BlockNumber>> {
+            AssemblyPallet::election_calendar()
+        }
+
+        fn get_current_assemblies() -> Vec<(pallet_voting::Candidate, u64)> {
+            AssemblyPallet::get_current_assemblies()
+        }
+
+        fn get_laws(state: pallet_assembly::LawState) -> BTreeMap<Hash, pallet_assembly::Law> {
+            AssemblyPallet::get_laws(state)
+        }
+
+        fn get_current_prime_minister() -> Option<pallet_voting::Candidate> {
+            AssemblyPallet::current_prime_min()
+        }
+
+        fn get_prime_min_candidates() -> BTreeSet<pallet_voting::Candidate> {
+            AssemblyPallet::prime_min_candidates_list()
+        }
+
+        fn get_next_election() -> BlockNumber {
+            AssemblyPallet::next_term_start()
+        }
+    }
+
+    impl pallet_voting::VotingPalletApi<Block, Runtime> for Runtime {
+        fn get_election_results(
+            subject: Hash,
+        ) -> BTreeMap<BlockNumber, pallet_voting::ElectionResult> {
+            VotingPallet::get_election_results(subject)
+        }
+
+        fn get_last_election_result(subject: Hash) -> Option<pallet_voting::ElectionResult> {
+            VotingPallet::get_last_election_result(subject)
+        }
+
+        fn get_active_votings() -> BTreeMap<Hash, pallet_voting::VotingInfo<BlockNumber>> {
+            VotingPallet::get_active_votings()
+        }
+
+        fn get_active_voting(subject: Hash) -> Option<pallet_voting::VotingInfo<BlockNumber>> {
+            VotingPallet::get_active_voting(subject)
+        }
     }
 
     #[cfg(feature = "runtime-benchmarks")]
@@ -790,3 +929,18 @@ impl_runtime_apis! {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voting_finalizations_fit_the_block() {
+        let max_block = RuntimeBlockWeights::get().max_block;
+        let budget = VotingPallet::finalizations_budget();
+        let finalizations =
+            MaxFinalizationsPerBlock::get() as Weight * VotingPallet::finalization_weight(0);
+        assert!(finalizations <= budget);
+        assert!(budget + VotingPallet::ballot_removal_weight() <= max_block);
+    }
+}